@@ -0,0 +1,364 @@
+use crate::anim::settings::AnimSettings;
+use crate::anim::{AnimFormat, Frames};
+use crate::image::Image;
+use crate::record::settings::RecordScale;
+use bytesize::ByteSize;
+use std::convert::TryFrom;
+
+/* Quality values to try while searching for the size budget */
+const QUALITY_STEPS: [u8; 4] = [50, 30, 20, 10];
+/* Palette sizes to try while searching for the size budget */
+const COLOR_STEPS: [u16; 4] = [128, 64, 32, 16];
+/* Frame steps (keeping every n-th frame) to try */
+const FRAME_STEPS: [usize; 3] = [2, 3, 4];
+/* Scale ratios to try while searching for the size budget */
+const SCALE_STEPS: [f32; 4] = [0.75, 0.5, 0.35, 0.25];
+/* Maximum number of frames to encode for estimating the output size */
+const SAMPLE_FRAMES: usize = 10;
+/* Tolerance of the estimated size since the samples are less similar */
+const ESTIMATE_TOLERANCE: f64 = 1.25;
+
+/**
+ * Parse the given size value. (e.g. "500KB", "5MB", "2MiB")
+ *
+ * @param  size
+ * @return u64 (Option)
+ */
+pub fn parse_size(size: &str) -> Option<u64> {
+	let size = size.trim();
+	let index = size
+		.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+		.unwrap_or(size.len());
+	let value = size[..index].parse::<f64>().ok()?;
+	let unit = match size[index..].trim().to_lowercase().as_str() {
+		"" | "b" => 1,
+		"k" | "kb" => 1_000,
+		"m" | "mb" => 1_000_000,
+		"g" | "gb" => 1_000_000_000,
+		"kib" => 1 << 10,
+		"mib" => 1 << 20,
+		"gib" => 1 << 30,
+		_ => return None,
+	};
+	match (value * unit as f64) as u64 {
+		0 => None,
+		size => Some(size),
+	}
+}
+
+/* Encoding parameters that are adjusted for fitting into the size budget */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BudgetKnobs {
+	pub quality: u8,
+	pub colors: u16,
+	pub frame_step: usize,
+	pub scale: f32,
+}
+
+impl BudgetKnobs {
+	/**
+	 * Create a new BudgetKnobs object.
+	 *
+	 * @param  quality
+	 * @param  colors
+	 * @param  frame_step
+	 * @param  scale
+	 * @return BudgetKnobs
+	 */
+	pub fn new(quality: u8, colors: u16, frame_step: usize, scale: f32) -> Self {
+		Self {
+			quality,
+			colors,
+			frame_step,
+			scale,
+		}
+	}
+
+	/**
+	 * Get the candidate knobs in the order of increasing quality loss.
+	 *
+	 * @param  quality
+	 * @param  colors
+	 * @return Vector of BudgetKnobs
+	 */
+	pub fn get_candidates(&self, quality: bool, colors: bool) -> Vec<Self> {
+		let mut knobs = *self;
+		let mut candidates = vec![knobs];
+		if quality {
+			for step in QUALITY_STEPS.iter().filter(|v| **v < self.quality) {
+				knobs.quality = *step;
+				candidates.push(knobs);
+			}
+		}
+		if colors {
+			for step in COLOR_STEPS.iter().filter(|v| **v < self.colors) {
+				knobs.colors = *step;
+				candidates.push(knobs);
+			}
+		}
+		for step in &FRAME_STEPS {
+			knobs.frame_step = *step;
+			candidates.push(knobs);
+		}
+		for step in &SCALE_STEPS {
+			knobs.scale = *step;
+			candidates.push(knobs);
+		}
+		candidates
+	}
+
+	/**
+	 * Apply the knobs to the frames.
+	 *
+	 * @param  frames
+	 * @return Frames
+	 */
+	pub fn apply(&self, frames: &Frames) -> Frames {
+		let scale = RecordScale {
+			ratio: self.scale,
+			..RecordScale::default()
+		};
+		(
+			frames
+				.0
+				.chunks(self.frame_step)
+				.map(|images| {
+					scale
+						.apply(images[0].clone())
+						.with_delay(images.iter().map(|image| image.delay).sum())
+				})
+				.collect(),
+			self.get_fps(frames.1),
+		)
+	}
+
+	/**
+	 * Get the FPS value after dropping the frames.
+	 *
+	 * @param  fps
+	 * @return u32
+	 */
+	pub fn get_fps(&self, fps: u32) -> u32 {
+		((fps as f32 / self.frame_step as f32).round() as u32).max(1)
+	}
+
+	/**
+	 * Get the descriptions of the changed knobs.
+	 *
+	 * @param  initial
+	 * @param  fps
+	 * @return Vector of String
+	 */
+	pub fn get_changes(&self, initial: &Self, fps: u32) -> Vec<String> {
+		let mut changes = Vec::new();
+		if self.quality != initial.quality {
+			changes
+				.push(format!("quality: {} -> {}", initial.quality, self.quality));
+		}
+		if self.colors != initial.colors {
+			changes.push(format!("colors: {} -> {}", initial.colors, self.colors));
+		}
+		if self.frame_step != initial.frame_step {
+			changes.push(format!("fps: {} -> {}", fps, self.get_fps(fps)));
+		}
+		if (self.scale - initial.scale).abs() > f32::EPSILON {
+			changes.push(format!("scale: {} -> {}", initial.scale, self.scale));
+		}
+		changes
+	}
+}
+
+/* Output size budget of the animation */
+#[derive(Debug)]
+pub struct SizeBudget<'a> {
+	max_size: u64,
+	format: AnimFormat,
+	settings: &'a AnimSettings,
+}
+
+impl<'a> SizeBudget<'a> {
+	/**
+	 * Create a new SizeBudget object.
+	 *
+	 * @param  max_size
+	 * @param  format
+	 * @param  settings
+	 * @return SizeBudget
+	 */
+	pub fn new(
+		max_size: u64,
+		format: AnimFormat,
+		settings: &'a AnimSettings,
+	) -> Self {
+		Self {
+			max_size,
+			format,
+			settings,
+		}
+	}
+
+	/**
+	 * Get the initial knobs from the animation settings.
+	 *
+	 * @return BudgetKnobs
+	 */
+	fn get_knobs(&self) -> BudgetKnobs {
+		BudgetKnobs::new(self.settings.quality, self.settings.colors, 1, 1.)
+	}
+
+	/**
+	 * Get the animation settings with the given knobs.
+	 *
+	 * @param  knobs
+	 * @param  fps
+	 * @return AnimSettings
+	 */
+	fn get_settings(&self, knobs: &BudgetKnobs, fps: u32) -> AnimSettings {
+		let mut settings = self.settings.clone();
+		settings.quality = knobs.quality;
+		settings.colors = knobs.colors;
+		settings.fps = knobs.get_fps(fps);
+		settings
+	}
+
+	/**
+	 * Estimate the output size by encoding a sample of the frames.
+	 *
+	 * @param  frames
+	 * @param  settings
+	 * @param  encode
+	 * @return u64 (Option)
+	 */
+	fn estimate<Encode>(
+		&self,
+		frames: &Frames,
+		settings: &AnimSettings,
+		encode: &Encode,
+	) -> Option<u64>
+	where
+		Encode: Fn(Frames, &AnimSettings, &mut Vec<u8>),
+	{
+		if frames.0.len() <= SAMPLE_FRAMES {
+			return None;
+		}
+		let sample = frames
+			.0
+			.iter()
+			.step_by(frames.0.len() / SAMPLE_FRAMES)
+			.take(SAMPLE_FRAMES)
+			.cloned()
+			.collect::<Vec<Image>>();
+		let mut output = Vec::new();
+		encode((sample, frames.1), settings, &mut output);
+		u64::try_from(output.len() * frames.0.len() / SAMPLE_FRAMES).ok()
+	}
+
+	/**
+	 * Encode the frames with the first knobs that fit into the budget.
+	 *
+	 * @param  frames
+	 * @param  encode
+	 * @return Vector of u8
+	 */
+	pub fn fit<Encode>(&self, frames: Frames, encode: Encode) -> Vec<u8>
+	where
+		Encode: Fn(Frames, &AnimSettings, &mut Vec<u8>),
+	{
+		let initial = self.get_knobs();
+		let candidates = initial.get_candidates(
+			self.format == AnimFormat::Gif,
+			self.format == AnimFormat::Gif && !self.settings.gifski.0,
+		);
+		let max_size = ByteSize(self.max_size).to_string_as(false);
+		let mut output = Vec::new();
+		for (i, knobs) in candidates.iter().enumerate() {
+			let settings = self.get_settings(knobs, frames.1);
+			let candidate = knobs.apply(&frames);
+			debug!("Trying to fit into {}: {:?}", max_size, knobs);
+			if i != candidates.len() - 1 {
+				if let Some(size) = self.estimate(&candidate, &settings, &encode) {
+					debug!("Estimated size: {}", ByteSize(size));
+					if size as f64 > self.max_size as f64 * ESTIMATE_TOLERANCE {
+						continue;
+					}
+				}
+			}
+			output.clear();
+			encode(candidate, &settings, &mut output);
+			if output.len() as u64 <= self.max_size {
+				let changes = knobs.get_changes(&initial, frames.1);
+				if changes.is_empty() {
+					info!("Output fits into {} without any changes.", max_size);
+				} else {
+					info!("Fitted into {} ({})", max_size, changes.join(", "));
+				}
+				return output;
+			}
+		}
+		warn!(
+			"Failed to fit into {} ({})",
+			max_size,
+			ByteSize(output.len() as u64)
+		);
+		output
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::gif::encoder::{Encoder, EncoderConfig};
+	use crate::gif::GifEncoder;
+	use crate::image::geometry::Geometry;
+	use crate::window::pattern::{Pattern, PatternWindow};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_size_budget() {
+		assert_eq!(Some(5_000_000), parse_size("5MB"));
+		assert_eq!(Some(1536), parse_size("1.5 KiB"));
+		assert_eq!(Some(100), parse_size("100"));
+		assert_eq!(None, parse_size("5XB"));
+		assert_eq!(None, parse_size("0"));
+		let knobs = BudgetKnobs::new(40, 256, 1, 1.);
+		let candidates = knobs.get_candidates(true, false);
+		assert_eq!(BudgetKnobs::new(30, 256, 1, 1.), candidates[1]);
+		assert_eq!(Some(&BudgetKnobs::new(10, 256, 4, 0.25)), candidates.last());
+		assert_eq!(
+			vec!["quality: 40 -> 10", "fps: 20 -> 5", "scale: 1 -> 0.25"],
+			candidates[candidates.len() - 1].get_changes(&knobs, 20)
+		);
+		assert_eq!(12, knobs.get_candidates(false, true).len());
+		let geometry = Geometry::new(0, 0, 64, 48);
+		let window = PatternWindow::new(Pattern::Noise, geometry);
+		let frames = ((0..12).map(|frame| window.get_frame(frame)).collect(), 10);
+		let applied = BudgetKnobs::new(75, 256, 3, 0.5).apply(&frames);
+		assert_eq!((4, 3), (applied.0.len(), applied.1));
+		assert_eq!(Geometry::new(0, 0, 32, 24), applied.0[0].geometry);
+		assert_eq!(None, applied.0[0].delay);
+		let frames = (
+			frames
+				.0
+				.into_iter()
+				.map(|image| image.with_delay(Some(40)))
+				.collect(),
+			10,
+		);
+		let applied = BudgetKnobs::new(75, 256, 3, 0.5).apply(&frames);
+		assert_eq!(Some(120), applied.0[3].delay);
+		let settings = AnimSettings::default();
+		let encode = |frames: Frames,
+		              settings: &AnimSettings,
+		              output: &mut Vec<u8>| {
+			let config =
+				EncoderConfig::new(frames.1, frames.0[0].geometry, output, settings);
+			GifEncoder::new(config).save(frames.0, None);
+		};
+		let mut output = Vec::new();
+		encode(frames.clone(), &settings, &mut output);
+		let max_size = output.len() as u64 / 3;
+		let output = SizeBudget::new(max_size, AnimFormat::Gif, &settings)
+			.fit(frames, encode);
+		assert!(!output.is_empty());
+		assert!(output.len() as u64 <= max_size);
+	}
+}
@@ -1,8 +1,8 @@
+use crate::anim::resample::FrameResampler;
 use crate::anim::settings::AnimSettings;
 use crate::anim::Frames;
 use crate::edit::ImageOps;
 use image::Frame;
-use std::convert::TryInto;
 use std::io::{self, Write};
 
 /* Animation decoder and settings */
@@ -23,32 +23,54 @@ impl<'a> AnimDecoder<'a> {
 		Self { imageops, settings }
 	}
 
+	/**
+	 * Get the delays of the frames (in milliseconds) with the speed applied.
+	 *
+	 * @param  frames
+	 * @return Vector of u32 (Option)
+	 */
+	fn get_delays(&self, frames: &[Frame]) -> Vec<Option<u32>> {
+		let delays = frames
+			.iter()
+			.map(|frame| {
+				let (numer, denom) = frame.delay().numer_denom_ms();
+				numer as f32 / denom.max(1) as f32
+			})
+			.collect::<Vec<f32>>();
+		match &self.settings.ramp {
+			Some(ramp) => ramp.get_delays(&delays, self.settings.speed),
+			None => delays
+				.iter()
+				.map(|delay| Some((delay / self.settings.speed).round() as u32))
+				.collect(),
+		}
+	}
+
 	/**
 	 * Update frames to cut the duration.
 	 *
 	 * @param  frames
 	 * @param  cut
-	 * @param  fps
-	 * @return Vector of Frame
+	 * @return Vector of Frame and delay
 	 */
 	fn cut_duration(
-		frames: &mut Vec<Frame>,
+		frames: Vec<(Frame, u32)>,
 		cut: (f32, f32),
-		fps: u32,
-	) -> Vec<Frame> {
+	) -> Vec<(Frame, u32)> {
 		if cut != (0., 0.) {
 			let (start, end) = cut;
-			let frame_delay = 1000_u32.checked_div(fps).unwrap_or_default() as f32;
+			let duration: u32 = frames.iter().map(|(_, delay)| delay).sum();
+			let mut time = 0;
 			frames
-				.drain(
-					((start / frame_delay) as u32)
-						.try_into()
-						.unwrap_or_default()
-						..frames.len().saturating_sub((end / frame_delay) as usize),
-				)
+				.into_iter()
+				.filter(|(_, delay)| {
+					let elapsed = time as f32;
+					time += delay;
+					time as f32 > start && (duration as f32 - elapsed) > end
+				})
 				.collect()
 		} else {
-			frames.to_vec()
+			frames
 		}
 	}
 
@@ -58,16 +80,24 @@ impl<'a> AnimDecoder<'a> {
 	 * @param  frames
 	 * @return Frames
 	 */
-	pub fn update_frames(mut self, mut frames: Vec<Frame>) -> Frames {
+	pub fn update_frames(mut self, frames: Vec<Frame>) -> Frames {
 		let first_frame = frames.first().expect("No frames found to process");
 		self.imageops
 			.init(first_frame.clone().into_buffer().dimensions());
 		let fps = ((1e3 / first_frame.delay().numer_denom_ms().0 as f32)
 			* self.settings.speed) as u32;
 		debug!("FPS: {:?}", fps);
-		let frames = Self::cut_duration(&mut frames, self.settings.cut, fps);
+		let delays = self.get_delays(&frames);
+		let frames = Self::cut_duration(
+			frames
+				.into_iter()
+				.zip(delays)
+				.filter_map(|(frame, delay)| delay.map(|delay| (frame, delay)))
+				.collect(),
+			self.settings.cut,
+		);
 		let mut images = Vec::new();
-		for (i, frame) in frames.iter().enumerate() {
+		for (i, (frame, delay)) in frames.iter().enumerate() {
 			let percentage = ((i + 1) as f64 / frames.len() as f64) * 100.;
 			info!("Processing the frames... ({:.1}%)\r", percentage);
 			debug!(
@@ -80,11 +110,24 @@ impl<'a> AnimDecoder<'a> {
 			images.push(
 				self.imageops
 					.process(frame.clone().into_buffer())
-					.get_image(),
+					.get_image()
+					.with_delay(Some(*delay)),
 			);
 		}
 		info!("\n");
-		(images, fps)
+		match self.settings.target_fps.or_else(|| {
+			self.settings
+				.retime
+				.map(|_| (fps as f32 / self.settings.speed).round() as u32)
+		}) {
+			Some(target_fps) => (
+				FrameResampler::new(target_fps, self.settings.blend)
+					.with_interpolation(self.settings.retime)
+					.resample(images),
+				target_fps,
+			),
+			None => (images, fps),
+		}
 	}
 }
 
@@ -120,5 +163,28 @@ mod tests {
 		assert_eq!(2, frames.1);
 		assert_eq!(1, frames.0.len());
 		assert_eq!(Geometry::new(0, 0, 2, 2), frames.0[0].geometry);
+		assert_eq!(Some(5), frames.0[0].delay);
+		let delays = [100, 20, 300, 40];
+		let frames = delays
+			.iter()
+			.map(|delay| {
+				(
+					Frame::from_parts(
+						RgbaImage::new(1, 1),
+						0,
+						0,
+						Delay::from_numer_denom_ms(*delay, 1),
+					),
+					*delay,
+				)
+			})
+			.collect();
+		assert_eq!(
+			vec![20, 300],
+			AnimDecoder::cut_duration(frames, (100., 40.))
+				.into_iter()
+				.map(|(_, delay)| delay)
+				.collect::<Vec<u32>>()
+		);
 	}
 }
@@ -0,0 +1,249 @@
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use image::ExtendedColorType;
+use std::str::FromStr;
+
+/* Size of the blocks for motion estimation */
+const BLOCK_SIZE: usize = 8;
+
+/* Initial step of the three-step motion search */
+const SEARCH_STEP: i32 = 4;
+
+/* Method for synthesizing the in-between frames */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+	Blend,
+	Motion,
+}
+
+/* Implementation for parsing Interpolation from a string */
+impl FromStr for Interpolation {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"blend" => Ok(Self::Blend),
+			"motion" => Ok(Self::Motion),
+			_ => Err("Unrecognized interpolation"),
+		}
+	}
+}
+
+impl Interpolation {
+	/**
+	 * Create an image from the RGBA pixels.
+	 *
+	 * @param  data
+	 * @param  geometry
+	 * @return Image
+	 */
+	fn get_image(data: &[f64], geometry: Geometry) -> Image {
+		Image::from_rgba(
+			&data
+				.iter()
+				.map(|value| value.round() as u8)
+				.collect::<Vec<u8>>(),
+			geometry,
+		)
+	}
+
+	/**
+	 * Blend the images using the given weights.
+	 *
+	 * @param  images
+	 * @return Image
+	 */
+	pub fn blend(images: &[(&Image, f64)]) -> Image {
+		let total: f64 = images.iter().map(|(_, weight)| weight).sum();
+		let mut data = Vec::<f64>::new();
+		for (image, weight) in images {
+			let pixels = image.get_data(ExtendedColorType::Rgba8);
+			data.resize(pixels.len(), 0.);
+			for (value, pixel) in data.iter_mut().zip(pixels) {
+				*value += f64::from(pixel) * weight / total;
+			}
+		}
+		Self::get_image(&data, images[0].0.geometry)
+	}
+
+	/**
+	 * Get the sum of absolute differences between two blocks.
+	 *
+	 * @param  first
+	 * @param  second
+	 * @param  size
+	 * @param  block
+	 * @param  offset
+	 * @return u64 (Option)
+	 */
+	fn get_cost(
+		first: &[u8],
+		second: &[u8],
+		size: (usize, usize),
+		block: (usize, usize),
+		offset: (i32, i32),
+	) -> Option<u64> {
+		let (x, y) = (block.0 as i32 + offset.0, block.1 as i32 + offset.1);
+		let (width, height) = (
+			BLOCK_SIZE.min(size.0 - block.0),
+			BLOCK_SIZE.min(size.1 - block.1),
+		);
+		if x < 0
+			|| y < 0 || x as usize + width > size.0
+			|| y as usize + height > size.1
+		{
+			return None;
+		}
+		let mut cost = 0;
+		for row in 0..height {
+			let start = ((block.1 + row) * size.0 + block.0) * 4;
+			let moved = ((y as usize + row) * size.0 + x as usize) * 4;
+			for i in 0..width * 4 {
+				cost += u64::from(
+					(i16::from(first[start + i]) - i16::from(second[moved + i]))
+						.unsigned_abs(),
+				);
+			}
+		}
+		Some(cost)
+	}
+
+	/**
+	 * Estimate the motion vector of a block with three-step search.
+	 *
+	 * @param  first
+	 * @param  second
+	 * @param  size
+	 * @param  block
+	 * @return Tuple (i32, i32)
+	 */
+	fn get_motion(
+		first: &[u8],
+		second: &[u8],
+		size: (usize, usize),
+		block: (usize, usize),
+	) -> (i32, i32) {
+		let mut vector = (0, 0);
+		let mut best =
+			Self::get_cost(first, second, size, block, vector).unwrap_or(u64::MAX);
+		let mut step = SEARCH_STEP;
+		while step > 0 && best > 0 {
+			let center = vector;
+			for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+			{
+				let offset = (center.0 + dx * step, center.1 + dy * step);
+				if let Some(cost) =
+					Self::get_cost(first, second, size, block, offset)
+				{
+					if cost < best {
+						best = cost;
+						vector = offset;
+					}
+				}
+			}
+			step /= 2;
+		}
+		vector
+	}
+
+	/**
+	 * Shift the frame along the block motion vectors and blend them.
+	 *
+	 * @param  first
+	 * @param  second
+	 * @param  ratio
+	 * @return Image
+	 */
+	fn compensate(first: &Image, second: &Image, ratio: f64) -> Image {
+		let geometry = first.geometry;
+		let size = (geometry.width as usize, geometry.height as usize);
+		let (first, second) = (
+			first.get_data(ExtendedColorType::Rgba8),
+			second.get_data(ExtendedColorType::Rgba8),
+		);
+		let mut data = vec![0.; size.0 * size.1 * 4];
+		let get_index = |x: f64, y: f64| {
+			let x = (x.round() as i64).clamp(0, size.0 as i64 - 1) as usize;
+			let y = (y.round() as i64).clamp(0, size.1 as i64 - 1) as usize;
+			(y * size.0 + x) * 4
+		};
+		for block_y in (0..size.1).step_by(BLOCK_SIZE) {
+			for block_x in (0..size.0).step_by(BLOCK_SIZE) {
+				let (dx, dy) =
+					Self::get_motion(&first, &second, size, (block_x, block_y));
+				let (dx, dy) = (f64::from(dx), f64::from(dy));
+				for y in block_y..(block_y + BLOCK_SIZE).min(size.1) {
+					for x in block_x..(block_x + BLOCK_SIZE).min(size.0) {
+						let (x, y) = (x as f64, y as f64);
+						let from = get_index(x - ratio * dx, y - ratio * dy);
+						let to =
+							get_index(x + (1. - ratio) * dx, y + (1. - ratio) * dy);
+						let index = get_index(x, y);
+						for i in 0..4 {
+							data[index + i] =
+								f64::from(first[from + i]) * (1. - ratio)
+									+ f64::from(second[to + i]) * ratio;
+						}
+					}
+				}
+			}
+		}
+		Self::get_image(&data, geometry)
+	}
+
+	/**
+	 * Synthesize a frame between two images.
+	 *
+	 * @param  first
+	 * @param  second
+	 * @param  ratio
+	 * @return Image
+	 */
+	pub fn interpolate(&self, first: &Image, second: &Image, ratio: f64) -> Image {
+		if first.geometry != second.geometry {
+			return first.clone();
+		}
+		match self {
+			Self::Blend => Self::blend(&[(first, 1. - ratio), (second, ratio)]),
+			Self::Motion => Self::compensate(first, second, ratio),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Bgra;
+	use pretty_assertions::assert_eq;
+	fn get_square(x: u32) -> Image {
+		let geometry = Geometry::new(0, 0, 32, 16);
+		let mut image =
+			Image::new(vec![Bgra::from([0, 0, 0, 255]); 32 * 16], true, geometry);
+		for y in 4..8 {
+			for x in x..x + 4 {
+				image.set_pixel(x as i32, y, Bgra::from([255, 255, 255, 255]));
+			}
+		}
+		image
+	}
+	fn get_row(image: &Image) -> Vec<u8> {
+		image.get_data(ExtendedColorType::Rgb8)[5 * 32 * 3..6 * 32 * 3]
+			.iter()
+			.step_by(3)
+			.copied()
+			.collect()
+	}
+	#[test]
+	fn test_interpolation() {
+		assert_eq!(Ok(Interpolation::Motion), Interpolation::from_str("motion"));
+		assert!(Interpolation::from_str("x").is_err());
+		let (first, second) = (get_square(4), get_square(8));
+		let image = Interpolation::Motion.interpolate(&first, &second, 0.5);
+		assert_eq!(get_row(&get_square(6)), get_row(&image));
+		let image = Interpolation::Blend.interpolate(&first, &second, 0.5);
+		assert_eq!(vec![128; 8], get_row(&image)[4..12].to_vec());
+		assert_eq!(
+			get_row(&first),
+			get_row(&Interpolation::Motion.interpolate(&first, &second, 0.))
+		);
+	}
+}
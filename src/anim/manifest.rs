@@ -0,0 +1,221 @@
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use image::ExtendedColorType;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/* Frame entry of the manifest */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFrame {
+	pub file: PathBuf,
+	pub delay: u32,
+	#[serde(default)]
+	pub left: u32,
+	#[serde(default)]
+	pub top: u32,
+}
+
+impl ManifestFrame {
+	/**
+	 * Create a new ManifestFrame object.
+	 *
+	 * @param  file
+	 * @param  delay
+	 * @param  left
+	 * @param  top
+	 * @return ManifestFrame
+	 */
+	pub fn new(file: PathBuf, delay: u32, left: u32, top: u32) -> Self {
+		Self {
+			file,
+			delay,
+			left,
+			top,
+		}
+	}
+}
+
+/* Animation manifest with the frame files and timing information */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+	#[serde(default)]
+	pub width: u32,
+	#[serde(default)]
+	pub height: u32,
+	#[serde(default)]
+	pub plays: u32,
+	pub frames: Vec<ManifestFrame>,
+}
+
+impl Manifest {
+	/**
+	 * Create a new Manifest object.
+	 *
+	 * @param  width
+	 * @param  height
+	 * @param  plays
+	 * @param  frames
+	 * @return Manifest
+	 */
+	pub fn new(
+		width: u32,
+		height: u32,
+		plays: u32,
+		frames: Vec<ManifestFrame>,
+	) -> Self {
+		Self {
+			width,
+			height,
+			plays,
+			frames,
+		}
+	}
+
+	/**
+	 * Parse a manifest from JSON.
+	 *
+	 * Frame files are resolved relative to the given directory.
+	 *
+	 * @param  value
+	 * @param  dir
+	 * @return Manifest (Option)
+	 */
+	pub fn parse(value: &str, dir: &Path) -> Option<Self> {
+		let manifest = serde_json::from_str::<Self>(value).ok()?;
+		Some(Self {
+			frames: manifest
+				.frames
+				.into_iter()
+				.map(|frame| ManifestFrame {
+					file: dir.join(frame.file),
+					..frame
+				})
+				.collect(),
+			..manifest
+		})
+	}
+
+	/**
+	 * Get the manifest as JSON.
+	 *
+	 * @return String
+	 */
+	pub fn get_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("Failed to serialize the manifest")
+			+ "\n"
+	}
+
+	/**
+	 * Place the images on the canvas and set their delays.
+	 *
+	 * @param  images
+	 * @return Vector of Image
+	 */
+	pub fn apply(&self, images: Vec<Image>) -> Vec<Image> {
+		let canvas = Geometry::new(
+			0,
+			0,
+			match self.width {
+				0 => images.first().map_or(0, |image| image.geometry.width),
+				width => width,
+			},
+			match self.height {
+				0 => images.first().map_or(0, |image| image.geometry.height),
+				height => height,
+			},
+		);
+		images
+			.into_iter()
+			.zip(&self.frames)
+			.map(|(image, frame)| {
+				let delay = Some(frame.delay);
+				if (frame.left, frame.top) == (0, 0)
+					&& (image.geometry.width, image.geometry.height)
+						== (canvas.width, canvas.height)
+				{
+					return image.with_delay(delay);
+				}
+				let data = image.get_data(ExtendedColorType::Rgba8);
+				let mut pixels =
+					vec![0; (canvas.width * canvas.height * 4) as usize];
+				let width = image.geometry.width as usize;
+				for (row, line) in data.chunks(width * 4).enumerate() {
+					let y = frame.top as usize + row;
+					if y >= canvas.height as usize {
+						break;
+					}
+					let x = frame.left as usize;
+					if x >= canvas.width as usize {
+						continue;
+					}
+					let len = line.len().min((canvas.width as usize - x) * 4);
+					let start = (y * canvas.width as usize + x) * 4;
+					pixels[start..start + len].copy_from_slice(&line[..len]);
+				}
+				Image::from_rgba(&pixels, canvas).with_delay(delay)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Bgra;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_manifest() {
+		let manifest = Manifest::new(
+			2,
+			2,
+			3,
+			vec![
+				ManifestFrame::new(PathBuf::from("frame_0.png"), 100, 0, 0),
+				ManifestFrame::new(PathBuf::from("a \"b\".png"), 250, 1, 1),
+			],
+		);
+		let json = manifest.get_json();
+		assert_eq!(
+			Some(manifest.clone()),
+			Manifest::parse(&json, Path::new(""))
+		);
+		assert_eq!(
+			Some(PathBuf::from("frames/frame_0.png")),
+			Manifest::parse(&json, Path::new("frames"))
+				.map(|manifest| manifest.frames[0].file.clone())
+		);
+		assert_eq!(
+			Some(Manifest::new(
+				0,
+				0,
+				0,
+				vec![ManifestFrame::new(PathBuf::from("x\u{e9}.png"), 40, 0, 0)]
+			)),
+			Manifest::parse(
+				r#"{"frames": [{"file": "xé.png", "delay": 40}], "x": null}"#,
+				Path::new("")
+			)
+		);
+		assert_eq!(None, Manifest::parse("{\"frames\": [{}]}", Path::new("")));
+		assert_eq!(None, Manifest::parse("{\"frames\": []} x", Path::new("")));
+		let images = manifest.apply(vec![
+			Image::new(
+				vec![Bgra::from([0, 0, 0, 255]); 4],
+				false,
+				Geometry::new(0, 0, 2, 2),
+			),
+			Image::new(
+				vec![Bgra::from([255, 255, 255, 255]); 4],
+				false,
+				Geometry::new(0, 0, 2, 2),
+			),
+		]);
+		assert_eq!(
+			vec![Some(100), Some(250)],
+			images.iter().map(|image| image.delay).collect::<Vec<_>>()
+		);
+		let mut data = vec![0; 12];
+		data.extend(vec![255; 4]);
+		assert_eq!(data, images[1].get_data(ExtendedColorType::Rgba8));
+	}
+}
@@ -1,7 +1,16 @@
+pub mod budget;
 pub mod decoder;
+pub mod interpolate;
+pub mod manifest;
+pub mod ramp;
+pub mod resample;
 pub mod settings;
+pub mod sheet;
+pub mod slideshow;
 
 use crate::image::Image;
+use image::ImageFormat;
+use std::convert::TryInto;
 use std::fmt;
 
 /* Images to encode and FPS value */
@@ -14,6 +23,52 @@ pub enum AnimFormat {
 	Apng,
 }
 
+impl AnimFormat {
+	/**
+	 * Guess the animation format from the file data.
+	 *
+	 * @param  data
+	 * @return AnimFormat (Option)
+	 */
+	pub fn from_data(data: &[u8]) -> Option<Self> {
+		match image::guess_format(data) {
+			Ok(ImageFormat::Gif) => Some(Self::Gif),
+			Ok(ImageFormat::Png) => Some(Self::Apng),
+			_ => None,
+		}
+	}
+
+	/**
+	 * Get the number of plays (0 for infinite) from the animation data.
+	 *
+	 * @param  data
+	 * @return u32
+	 */
+	pub fn get_plays(&self, data: &[u8]) -> u32 {
+		let (chunk, offset): (&[u8], usize) = match self {
+			AnimFormat::Gif => (b"NETSCAPE2.0", 13),
+			AnimFormat::Apng => (b"acTL", 8),
+		};
+		let value = data
+			.windows(chunk.len())
+			.position(|window| window == chunk)
+			.and_then(|position| data.get(position + offset..position + offset + 4));
+		match (self, value) {
+			(AnimFormat::Gif, Some(value)) => {
+				match u16::from_le_bytes([value[0], value[1]]) {
+					0 => 0,
+					count => u32::from(count) + 1,
+				}
+			}
+			(AnimFormat::Gif, None) => 1,
+			(AnimFormat::Apng, Some(value)) => {
+				u32::from_be_bytes(value.try_into().unwrap_or_default())
+			}
+			(AnimFormat::Apng, None) => 0,
+		}
+	}
+}
+
 /* Display implementation for user-facing output */
 impl fmt::Display for AnimFormat {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -88,6 +143,15 @@ mod tests {
 	fn test_anim_mode() {
 		let anim_format = AnimFormat::Apng;
 		assert_eq!("Apng", anim_format.to_string().as_str());
+		assert_eq!(0, anim_format.get_plays(&[]));
+		assert_eq!(Some(AnimFormat::Gif), AnimFormat::from_data(b"GIF89a"));
+		assert_eq!(None, AnimFormat::from_data(&[]));
+		assert_eq!(1, AnimFormat::Gif.get_plays(&[]));
+		assert_eq!(
+			3,
+			AnimFormat::Gif
+				.get_plays(b"GIF89a\x21\xffNETSCAPE2.0\x03\x01\x02\x00\x00\x3b")
+		);
 		let anim_mode = AnimMode::Record(anim_format);
 		assert!(!anim_mode.is_edit());
 		assert!(anim_mode.has_format(AnimFormat::Apng));
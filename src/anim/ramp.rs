@@ -0,0 +1,207 @@
+use crate::image::Image;
+
+/* Minimum frame delay (in milliseconds) that is respected by the viewers */
+pub const MIN_DELAY: u32 = 20;
+
+/* Speed of a time range (in milliseconds) */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeedSegment {
+	pub start: f32,
+	pub end: Option<f32>,
+	pub speed: f32,
+}
+
+/* Speed segments across the animation */
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedRamp {
+	segments: Vec<SpeedSegment>,
+}
+
+impl SpeedRamp {
+	/**
+	 * Create a new SpeedRamp object.
+	 *
+	 * @param  segments
+	 * @return SpeedRamp
+	 */
+	pub fn new(segments: Vec<SpeedSegment>) -> Self {
+		Self { segments }
+	}
+
+	/**
+	 * Parse a time value (in seconds or milliseconds) into milliseconds.
+	 *
+	 * @param  value
+	 * @return f32 (Option)
+	 */
+	fn parse_time(value: &str) -> Option<f32> {
+		let value = value.trim().to_lowercase();
+		if let Some(value) = value.strip_suffix("ms") {
+			value.parse().ok()
+		} else {
+			value
+				.trim_end_matches('s')
+				.parse::<f32>()
+				.ok()
+				.map(|seconds| seconds * 1000.)
+		}
+	}
+
+	/**
+	 * Parse speed segments from a string (e.g. "0-5s:1x,5-40s:4x,40s-:1x").
+	 *
+	 * @param  value
+	 * @return SpeedRamp (Option)
+	 */
+	pub fn parse(value: &str) -> Option<Self> {
+		let mut segments = Vec::new();
+		for segment in value.split(',') {
+			let (range, speed) = segment.split_at(segment.find(':')?);
+			let (start, end) = range.split_at(range.find('-')?);
+			let speed = speed[1..]
+				.trim()
+				.trim_end_matches('x')
+				.parse::<f32>()
+				.ok()?;
+			if speed <= 0. {
+				return None;
+			}
+			segments.push(SpeedSegment {
+				start: if start.trim().is_empty() {
+					0.
+				} else {
+					Self::parse_time(start)?
+				},
+				end: match end[1..].trim() {
+					"" => None,
+					end => Some(Self::parse_time(end)?),
+				},
+				speed,
+			});
+		}
+		Some(Self::new(segments))
+	}
+
+	/**
+	 * Get the speed at the given time.
+	 *
+	 * @param  time
+	 * @return f32
+	 */
+	pub fn get_speed(&self, time: f32) -> f32 {
+		self.segments
+			.iter()
+			.find(|segment| {
+				time >= segment.start
+					&& !matches!(segment.end, Some(end) if time >= end)
+			})
+			.map_or(1., |segment| segment.speed)
+	}
+
+	/**
+	 * Get the frame delays after applying the speed segments.
+	 *
+	 * Frames are dropped (None) when their delays fall below the minimum,
+	 * and their durations are added to the previous frame.
+	 *
+	 * @param  delays
+	 * @param  speed
+	 * @return Vector of u32 (Option)
+	 */
+	pub fn get_delays(&self, delays: &[f32], speed: f32) -> Vec<Option<u32>> {
+		let mut kept: Vec<(usize, f32)> = Vec::new();
+		let mut time = 0.;
+		for (i, delay) in delays.iter().enumerate() {
+			let new_delay = delay / (speed * self.get_speed(time + delay / 2.));
+			time += delay;
+			match kept.last_mut() {
+				Some((_, last)) if *last < MIN_DELAY as f32 => *last += new_delay,
+				_ => kept.push((i, new_delay)),
+			}
+		}
+		if kept.len() > 1 && kept[kept.len() - 1].1 < MIN_DELAY as f32 {
+			if let Some((_, delay)) = kept.pop() {
+				if let Some((_, last)) = kept.last_mut() {
+					*last += delay;
+				}
+			}
+		}
+		let mut new_delays = vec![None; delays.len()];
+		let mut elapsed: f32 = 0.;
+		for (i, delay) in kept {
+			new_delays[i] =
+				Some((elapsed + delay).round() as u32 - elapsed.round() as u32);
+			elapsed += delay;
+		}
+		new_delays
+	}
+
+	/**
+	 * Apply the speed segments to the images.
+	 *
+	 * @param  images
+	 * @param  fps
+	 * @param  speed
+	 * @return Vector of Image
+	 */
+	pub fn apply(&self, images: Vec<Image>, fps: u32, speed: f32) -> Vec<Image> {
+		let delays = images
+			.iter()
+			.map(|image| image.get_delay(fps))
+			.collect::<Vec<f32>>();
+		images
+			.into_iter()
+			.zip(self.get_delays(&delays, speed))
+			.filter_map(|(image, delay)| delay.map(|v| image.with_delay(Some(v))))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_speed_ramp() {
+		let ramp = SpeedRamp::parse("0-5s:1x,5-40s:4x,40s-:1x").unwrap();
+		assert_eq!(
+			SpeedSegment {
+				start: 5000.,
+				end: Some(40000.),
+				speed: 4.,
+			},
+			ramp.segments[1]
+		);
+		assert_eq!(1., ramp.get_speed(4999.));
+		assert_eq!(4., ramp.get_speed(5000.));
+		assert_eq!(1., ramp.get_speed(60000.));
+		assert_eq!(
+			Some(SpeedRamp::new(vec![SpeedSegment {
+				start: 0.,
+				end: Some(500.),
+				speed: 0.5,
+			}])),
+			SpeedRamp::parse("-500ms:0.5")
+		);
+		assert_eq!(None, SpeedRamp::parse("1.5"));
+		assert_eq!(None, SpeedRamp::parse("0-1s:0x"));
+		let ramp = SpeedRamp::parse("100ms-300ms:10x").unwrap();
+		assert_eq!(
+			vec![Some(50), Some(20), None, Some(55), None],
+			ramp.get_delays(&[50., 100., 100., 50., 50.], 1.)
+		);
+		assert_eq!(
+			vec![Some(65), None, None],
+			ramp.get_delays(&[50., 100., 50.], 1.)
+		);
+		let images = (0..10)
+			.map(|_| Image::new(Vec::new(), false, Geometry::default()))
+			.collect();
+		let images = SpeedRamp::parse("0-:2x").unwrap().apply(images, 20, 2.);
+		assert_eq!(
+			vec![Some(25); 5],
+			images.iter().map(|image| image.delay).collect::<Vec<_>>()
+		);
+	}
+}
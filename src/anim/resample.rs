@@ -0,0 +1,229 @@
+use crate::anim::interpolate::Interpolation;
+use crate::image::Image;
+
+/* Frame rate converter for the animation frames */
+#[derive(Clone, Copy, Debug)]
+pub struct FrameResampler {
+	fps: u32,
+	blend: bool,
+	interpolation: Option<Interpolation>,
+}
+
+impl FrameResampler {
+	/**
+	 * Create a new FrameResampler object.
+	 *
+	 * @param  fps
+	 * @param  blend
+	 * @return FrameResampler
+	 */
+	pub fn new(fps: u32, blend: bool) -> Self {
+		Self {
+			fps,
+			blend,
+			interpolation: None,
+		}
+	}
+
+	/**
+	 * Set the method for synthesizing the in-between frames.
+	 *
+	 * @param  interpolation (Option)
+	 * @return FrameResampler
+	 */
+	pub fn with_interpolation(
+		mut self,
+		interpolation: Option<Interpolation>,
+	) -> Self {
+		self.interpolation = interpolation;
+		self
+	}
+
+	/**
+	 * Get the start and end times (in milliseconds) of the images.
+	 *
+	 * @param  images
+	 * @return Vector of tuple (f64, f64)
+	 */
+	fn get_timeline(images: &[Image]) -> Vec<(f64, f64)> {
+		let mut time = 0.;
+		images
+			.iter()
+			.map(|image| {
+				let start = time;
+				time += f64::from(image.delay.unwrap_or_default());
+				(start, time)
+			})
+			.collect()
+	}
+
+	/**
+	 * Apply the speed to the images and resample them to the FPS value.
+	 *
+	 * @param  images
+	 * @param  speed
+	 * @return Vector of Image
+	 */
+	pub fn retime(&self, images: Vec<Image>, speed: f32) -> Vec<Image> {
+		if speed <= 0.
+			|| ((speed - 1.).abs() <= f32::EPSILON && self.interpolation.is_none())
+		{
+			return images;
+		}
+		let images = images
+			.into_iter()
+			.map(|image| {
+				let delay = image.get_delay(self.fps);
+				image.with_delay(Some((delay / speed).round() as u32))
+			})
+			.collect();
+		if self.interpolation.is_some() {
+			self.resample(images)
+		} else {
+			images
+		}
+	}
+
+	/**
+	 * Resample the images to the FPS value while keeping the duration.
+	 *
+	 * @param  images
+	 * @return Vector of Image
+	 */
+	pub fn resample(&self, images: Vec<Image>) -> Vec<Image> {
+		let timeline = Self::get_timeline(&images);
+		let duration = timeline.last().map_or(0., |(_, end)| *end);
+		if duration <= 0. || self.fps == 0 {
+			return images;
+		}
+		let count = ((duration * f64::from(self.fps) / 1e3).round() as usize).max(1);
+		let interval = duration / count as f64;
+		debug!("Resampling {} frames to {}", images.len(), count);
+		(0..count)
+			.map(|i| {
+				let (start, end) = (i as f64 * interval, (i + 1) as f64 * interval);
+				let overlaps = images
+					.iter()
+					.zip(&timeline)
+					.filter_map(|(image, (frame_start, frame_end))| {
+						let overlap = end.min(*frame_end) - start.max(*frame_start);
+						if overlap > 0. {
+							Some((image, overlap))
+						} else {
+							None
+						}
+					})
+					.collect::<Vec<(&Image, f64)>>();
+				let image = if (self.blend || self.interpolation.is_some())
+					&& overlaps.len() > 1
+				{
+					Interpolation::blend(&overlaps)
+				} else if let (Some(interpolation), Some(index)) = (
+					self.interpolation,
+					timeline.iter().position(|(_, end)| *end > start),
+				) {
+					let (frame_start, frame_end) = timeline[index];
+					match images.get(index + 1) {
+						Some(next) if start > frame_start => interpolation
+							.interpolate(
+								&images[index],
+								next,
+								(start - frame_start) / (frame_end - frame_start),
+							),
+						_ => images[index].clone(),
+					}
+				} else {
+					overlaps.first().map_or_else(
+						|| images[images.len() - 1].clone(),
+						|overlap| overlap.0.clone(),
+					)
+				};
+				image.with_delay(Some((end.round() - start.round()) as u32))
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use image::{Bgra, ExtendedColorType};
+	use pretty_assertions::assert_eq;
+	fn get_images(colors: &[u8], delay: u32) -> Vec<Image> {
+		colors
+			.iter()
+			.map(|color| {
+				Image::new(
+					vec![Bgra::from([*color, *color, *color, 255])],
+					true,
+					Geometry::new(0, 0, 1, 1),
+				)
+				.with_delay(Some(delay))
+			})
+			.collect()
+	}
+	#[test]
+	fn test_frame_resampler() {
+		let images = get_images(&[0, 50, 100, 150, 200, 250], 100);
+		let resampled = FrameResampler::new(5, false).resample(images.clone());
+		assert_eq!(
+			vec![Some(200); 3],
+			resampled
+				.iter()
+				.map(|image| image.delay)
+				.collect::<Vec<_>>()
+		);
+		assert_eq!(
+			vec![0, 100, 200],
+			resampled
+				.iter()
+				.map(|image| image.get_data(ExtendedColorType::L8)[0])
+				.collect::<Vec<u8>>()
+		);
+		let resampled = FrameResampler::new(20, false).resample(images.clone());
+		assert_eq!(12, resampled.len());
+		assert_eq!(
+			resampled[2].get_data(ExtendedColorType::Rgba8),
+			resampled[3].get_data(ExtendedColorType::Rgba8)
+		);
+		let resampled = FrameResampler::new(5, true).resample(images);
+		assert_eq!(
+			vec![25, 125, 225],
+			resampled
+				.iter()
+				.map(|image| image.get_data(ExtendedColorType::Rgb8)[0])
+				.collect::<Vec<u8>>()
+		);
+		let resampled = FrameResampler::new(20, false)
+			.with_interpolation(Some(Interpolation::Blend))
+			.resample(get_images(&[0, 100], 100));
+		assert_eq!(
+			vec![0, 50, 100, 100],
+			resampled
+				.iter()
+				.map(|image| image.get_data(ExtendedColorType::Rgb8)[0])
+				.collect::<Vec<u8>>()
+		);
+		let resampler = FrameResampler::new(10, false)
+			.with_interpolation(Some(Interpolation::Blend));
+		assert_eq!(4, resampler.retime(get_images(&[0, 100], 100), 0.5).len());
+		assert_eq!(
+			vec![25, 175],
+			resampler
+				.retime(get_images(&[0, 50, 150, 200], 100), 2.)
+				.iter()
+				.map(|image| image.get_data(ExtendedColorType::Rgb8)[0])
+				.collect::<Vec<u8>>()
+		);
+		let resampled =
+			FrameResampler::new(7, false).resample(get_images(&[0, 0, 0], 100));
+		assert_eq!(
+			300,
+			resampled
+				.iter()
+				.map(|image| image.delay.unwrap_or_default())
+				.sum::<u32>()
+		);
+	}
+}
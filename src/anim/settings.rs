@@ -1,19 +1,40 @@
+use crate::anim::budget;
+use crate::anim::interpolate::Interpolation;
+use crate::anim::manifest::Manifest;
+use crate::anim::ramp::SpeedRamp;
+use crate::anim::sheet::{SheetLayout, SpriteSheet};
+use crate::anim::slideshow::Transition;
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
 use crate::file::format::FileFormat;
 use crate::file::File;
+use image::ImageFormat;
+use std::convert::TryFrom;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /* Animation and frame settings */
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AnimSettings {
 	pub fps: u32,
 	pub repeat: i32,
+	pub keep_repeat: bool,
 	pub quality: u8,
+	pub colors: u16,
+	pub lossy: u8,
+	pub max_size: Option<u64>,
 	pub speed: f32,
+	pub ramp: Option<SpeedRamp>,
 	pub cut: (f32, f32),
+	pub target_fps: Option<u32>,
+	pub blend: bool,
+	pub retime: Option<Interpolation>,
+	pub hold: Vec<u32>,
+	pub transition: Option<(Transition, u32)>,
 	pub frames: Vec<PathBuf>,
+	pub manifest: Option<Manifest>,
+	pub sheet: Option<SpriteSheet>,
 	pub gifski: (bool, bool),
 }
 
@@ -23,10 +44,22 @@ impl Default for AnimSettings {
 		Self {
 			fps: 20,
 			repeat: -1,
+			keep_repeat: false,
 			quality: 75,
+			colors: 256,
+			lossy: 0,
+			max_size: None,
 			speed: 1.,
+			ramp: None,
 			cut: (0., 0.),
+			target_fps: None,
+			blend: false,
+			retime: None,
+			hold: Vec::new(),
+			transition: None,
 			frames: Vec::new(),
+			manifest: None,
+			sheet: None,
 			gifski: (false, false),
 		}
 	}
@@ -39,28 +72,63 @@ impl AnimSettings {
 	 * @param  fps
 	 * @param  repeat
 	 * @param  quality
+	 * @param  colors
+	 * @param  lossy
+	 * @param  max_size (Option)
 	 * @param  speed
+	 * @param  ramp (Option)
 	 * @param  cut
+	 * @param  target_fps (Option)
+	 * @param  blend
+	 * @param  retime (Option)
+	 * @param  hold
+	 * @param  transition (Option)
 	 * @param  frames
+	 * @param  manifest (Option)
+	 * @param  sheet (Option)
 	 * @param  gifski
 	 * @return AnimSettings
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		fps: u32,
 		repeat: i32,
 		quality: u8,
+		colors: u16,
+		lossy: u8,
+		max_size: Option<u64>,
 		speed: f32,
+		ramp: Option<SpeedRamp>,
 		cut: (f32, f32),
+		target_fps: Option<u32>,
+		blend: bool,
+		retime: Option<Interpolation>,
+		hold: Vec<u32>,
+		transition: Option<(Transition, u32)>,
 		frames: Vec<PathBuf>,
+		manifest: Option<Manifest>,
+		sheet: Option<SpriteSheet>,
 		gifski: (bool, bool),
 	) -> Self {
 		Self {
 			fps,
 			repeat,
+			keep_repeat: false,
 			quality,
+			colors,
+			lossy,
+			max_size,
 			speed,
+			ramp,
 			cut,
+			target_fps,
+			blend,
+			retime,
+			hold,
+			transition,
 			frames,
+			manifest,
+			sheet,
 			gifski,
 		}
 	}
@@ -74,14 +142,37 @@ impl AnimSettings {
 	 */
 	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
 		let format = &file_format.to_string().to_lowercase();
-		Self::from_parser(ArgParser::from_subcommand(
+		let parser = ArgParser::from_subcommand(
 			matches,
 			if matches.is_present("make") {
 				"make"
 			} else {
 				format
 			},
-		))
+		);
+		let repeat = parser
+			.args
+			.as_ref()
+			.map_or(0, |args| args.occurrences_of("repeat"));
+		let settings = Self::from_parser(parser);
+		if let Some(plays) = settings
+			.manifest
+			.as_ref()
+			.map(|manifest| manifest.plays)
+			.or_else(|| settings.sheet.as_ref().map(|sheet| sheet.plays))
+			.filter(|_| repeat == 0)
+		{
+			return Self {
+				repeat: i32::try_from(plays).unwrap_or_default() - 1,
+				..settings
+			};
+		}
+		Self {
+			keep_repeat: repeat == 0
+				&& matches.is_present("edit")
+				&& file_format.is_animation(),
+			..settings
+		}
 	}
 
 	/**
@@ -92,28 +183,158 @@ impl AnimSettings {
 	 */
 	fn from_parser(parser: ArgParser<'_>) -> Self {
 		match parser.args {
-			Some(ref matches) => Self::new(
-				match parser.parse("fps", Self::default().fps) {
-					fps if fps > 0 => fps,
-					_ => Self::default().fps,
-				},
-				parser.parse("repeat", Self::default().repeat) - 1,
-				parser.parse("quality", Self::default().quality),
-				parser.parse("speed", Self::default().speed),
-				(
-					parser.parse("cut-beginning", Self::default().cut.0) * 1000.,
-					parser.parse("cut-end", Self::default().cut.1) * 1000.,
-				),
-				Self::get_frames(&matches),
-				(
-					matches.is_present("gifski") || matches.is_present("fast"),
-					matches.is_present("fast"),
-				),
-			),
+			Some(ref matches) => {
+				let manifest = matches.value_of("manifest").map(|file| {
+					Manifest::parse(
+						&fs::read_to_string(file)
+							.expect("Failed to read the manifest"),
+						Path::new(file).parent().unwrap_or_else(|| Path::new("")),
+					)
+					.unwrap_or_else(|| panic!("Invalid manifest ({})", file))
+				});
+				Self::new(
+					match parser.parse("fps", Self::default().fps) {
+						fps if fps > 0 => fps,
+						_ => Self::default().fps,
+					},
+					parser.parse("repeat", Self::default().repeat) - 1,
+					parser.parse("quality", Self::default().quality),
+					Self::default().colors,
+					parser.parse("lossy", Self::default().lossy).min(200),
+					matches.value_of("max-size").map(|size| {
+						budget::parse_size(size)
+							.unwrap_or_else(|| panic!("Invalid size ({})", size))
+					}),
+					parser.parse("speed", Self::default().speed),
+					matches
+						.value_of("speed")
+						.filter(|speed| speed.contains(':'))
+						.map(|speed| {
+							SpeedRamp::parse(speed).unwrap_or_else(|| {
+								panic!("Invalid speed segments ({})", speed)
+							})
+						}),
+					(
+						parser.parse("cut-beginning", Self::default().cut.0) * 1000.,
+						parser.parse("cut-end", Self::default().cut.1) * 1000.,
+					),
+					match parser.parse("target-fps", 0) {
+						fps if fps > 0 => Some(fps),
+						_ => None,
+					},
+					matches.is_present("blend"),
+					matches
+						.value_of("retime")
+						.and_then(|value| Interpolation::from_str(value).ok()),
+					matches
+						.value_of("hold")
+						.map(|hold| {
+							hold.split(',')
+								.map(|seconds| {
+									(seconds.trim().parse::<f32>().unwrap_or_else(
+										|_| {
+											panic!(
+												"Invalid hold duration ({})",
+												hold
+											)
+										},
+									) * 1000.) as u32
+								})
+								.collect()
+						})
+						.unwrap_or_default(),
+					matches
+						.value_of("transition")
+						.and_then(|value| Transition::from_str(value).ok())
+						.map(|transition| {
+							(
+								transition,
+								(parser.parse("transition-duration", 0.5) * 1000.)
+									as u32,
+							)
+						}),
+					match &manifest {
+						Some(manifest) => manifest
+							.frames
+							.iter()
+							.map(|frame| frame.file.clone())
+							.collect(),
+						None => Self::get_frames(&matches),
+					},
+					manifest,
+					Self::get_sheet(&matches),
+					(
+						matches.is_present("gifski") || matches.is_present("fast"),
+						matches.is_present("fast"),
+					),
+				)
+			}
 			None => Self::default(),
 		}
 	}
 
+	/**
+	 * Get the settings with the loop count of the source animation.
+	 *
+	 * @param  plays (Option)
+	 * @return AnimSettings
+	 */
+	pub fn with_plays(&self, plays: Option<u32>) -> Self {
+		match plays.filter(|_| self.keep_repeat) {
+			Some(plays) => Self {
+				repeat: i32::try_from(plays).unwrap_or_default() - 1,
+				..self.clone()
+			},
+			None => self.clone(),
+		}
+	}
+
+	/**
+	 * Get the sprite sheet from parsed arguments.
+	 *
+	 * @param  args
+	 * @return SpriteSheet (Option)
+	 */
+	fn get_sheet(args: &ArgMatches<'_>) -> Option<SpriteSheet> {
+		if let Some(atlas) = args.value_of("atlas") {
+			let sheet = SpriteSheet::parse(
+				&fs::read_to_string(atlas).expect("Failed to read the atlas"),
+				Path::new(atlas).parent().unwrap_or_else(|| Path::new("")),
+			)
+			.unwrap_or_else(|| panic!("Invalid atlas ({})", atlas));
+			Some(match args.value_of("sheet") {
+				Some(file) => SpriteSheet {
+					file: PathBuf::from(file),
+					..sheet
+				},
+				None => sheet,
+			})
+		} else if let Some(file) = args.value_of("sheet") {
+			let grid = args.value_of("grid").expect(
+				"Grid dimensions or atlas are required for the sprite sheet",
+			);
+			let (columns, rows) = match grid
+				.split('x')
+				.map(|v| v.trim().parse::<u32>())
+				.collect::<Vec<_>>()
+				.as_slice()
+			{
+				[Ok(columns), Ok(rows)] if *columns > 0 && *rows > 0 => {
+					(*columns, *rows)
+				}
+				_ => panic!("Invalid grid ({})", grid),
+			};
+			Some(SpriteSheet::from_grid(
+				PathBuf::from(file),
+				image::image_dimensions(file)
+					.expect("Failed to read the sprite sheet"),
+				(columns, rows),
+			))
+		} else {
+			None
+		}
+	}
+
 	/**
 	 * Get the frame files from parsed arguments.
 	 *
@@ -124,14 +345,9 @@ impl AnimSettings {
 		let mut values = if let Some(dir) = args.value_of("dir") {
 			fs::read_dir(dir)
 				.expect("Could not read files from directory")
-				.map(|entry| {
-					entry
-						.expect("Failed to get directory entry")
-						.path()
-						.into_os_string()
-						.into_string()
-						.unwrap_or_default()
-				})
+				.map(|entry| entry.expect("Failed to get directory entry").path())
+				.filter(|path| ImageFormat::from_path(path).is_ok())
+				.map(|path| path.into_os_string().into_string().unwrap_or_default())
 				.collect()
 		} else if let Some(values) = args.values_of("frames") {
 			values.map(String::from).collect()
@@ -169,6 +385,7 @@ impl AnimSettings {
 pub struct SplitSettings {
 	pub file: PathBuf,
 	pub dir: PathBuf,
+	pub sheet: Option<(SheetLayout, u32)>,
 }
 
 /* Default initialization values for SplitSettings */
@@ -177,6 +394,7 @@ impl Default for SplitSettings {
 		Self {
 			file: PathBuf::new(),
 			dir: PathBuf::new(),
+			sheet: None,
 		}
 	}
 }
@@ -187,10 +405,15 @@ impl SplitSettings {
 	 *
 	 * @param  file
 	 * @param  dir
+	 * @param  sheet (Option)
 	 * @return SplitSettings
 	 */
-	pub fn new(file: PathBuf, dir: PathBuf) -> Self {
-		Self { file, dir }
+	pub fn new(
+		file: PathBuf,
+		dir: PathBuf,
+		sheet: Option<(SheetLayout, u32)>,
+	) -> Self {
+		Self { file, dir, sheet }
 	}
 
 	/**
@@ -211,7 +434,7 @@ impl SplitSettings {
 	 */
 	fn from_parser(parser: ArgParser<'_>) -> Self {
 		match parser.args {
-			Some(matches) => {
+			Some(ref matches) => {
 				let file =
 					PathBuf::from(matches.value_of("file").unwrap_or_default());
 				let dir = match matches.value_of("dir") {
@@ -224,7 +447,11 @@ impl SplitSettings {
 							.unwrap_or_default(),
 					)),
 				};
-				Self::new(file, dir)
+				let sheet = matches
+					.value_of("sheet")
+					.and_then(|value| SheetLayout::from_str(value).ok())
+					.map(|layout| (layout, parser.parse("columns", 0)));
+				Self::new(file, dir, sheet)
 			}
 			None => Self::default(),
 		}
@@ -252,6 +479,30 @@ mod tests {
 					.takes_value(true),
 			)
 			.arg(Arg::with_name("cut-end").long("cut-end").takes_value(true))
+			.arg(
+				Arg::with_name("target-fps")
+					.long("target-fps")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("blend").long("blend"))
+			.arg(Arg::with_name("retime").long("retime").takes_value(true))
+			.arg(Arg::with_name("hold").long("hold").takes_value(true))
+			.arg(
+				Arg::with_name("transition")
+					.long("transition")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("transition-duration")
+					.long("transition-duration")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("lossy").long("lossy").takes_value(true))
+			.arg(
+				Arg::with_name("max-size")
+					.long("max-size")
+					.takes_value(true),
+			)
 			.get_matches_from(vec![
 				"test",
 				"--fps",
@@ -268,6 +519,21 @@ mod tests {
 				"0.9",
 				"--cut-end",
 				"0.8",
+				"--max-size",
+				"2MB",
+				"--lossy",
+				"250",
+				"--target-fps",
+				"10",
+				"--blend",
+				"--retime",
+				"motion",
+				"--hold",
+				"2,0.5",
+				"--transition",
+				"slide",
+				"--transition-duration",
+				"0.25",
 			]);
 		let anim_settings = AnimSettings::from_parser(ArgParser::from_args(&args));
 		assert_eq!(15, anim_settings.fps);
@@ -277,13 +543,42 @@ mod tests {
 		assert_eq!(true, anim_settings.gifski.1);
 		assert_eq!(1.1, anim_settings.speed);
 		assert_eq!((900., 800.), anim_settings.cut);
+		assert_eq!(Some(2_000_000), anim_settings.max_size);
+		assert_eq!(200, anim_settings.lossy);
+		assert_eq!(Some(10), anim_settings.target_fps);
+		assert!(anim_settings.blend);
+		assert_eq!(Some(Interpolation::Motion), anim_settings.retime);
+		assert_eq!(vec![2000, 500], anim_settings.hold);
+		assert_eq!(Some((Transition::Slide, 250)), anim_settings.transition);
 		let anim_settings = AnimSettings::from_parser(ArgParser::new(None));
 		assert_eq!(-1, anim_settings.repeat);
 		assert_eq!(75, anim_settings.quality);
+		assert_eq!(256, anim_settings.colors);
+		assert_eq!(None, anim_settings.max_size);
 		assert_eq!(false, anim_settings.gifski.0);
 		assert_eq!(false, anim_settings.gifski.1);
 		assert_eq!(1.0, anim_settings.speed);
 		assert_eq!((0., 0.), anim_settings.cut);
+		assert_eq!(None, anim_settings.target_fps);
+		assert_eq!(None, anim_settings.ramp);
+		assert_eq!(None, anim_settings.transition);
+		let args = App::new("test")
+			.arg(Arg::with_name("speed").long("speed").takes_value(true))
+			.get_matches_from(vec!["test", "--speed", "0-1s:2x"]);
+		let anim_settings = AnimSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(1., anim_settings.speed);
+		assert_eq!(
+			Some(2.),
+			anim_settings.ramp.as_ref().map(|ramp| ramp.get_speed(0.))
+		);
+		assert_eq!(-2, anim_settings.with_plays(Some(1)).repeat);
+		let anim_settings = AnimSettings {
+			keep_repeat: true,
+			..anim_settings
+		};
+		assert_eq!(0, anim_settings.with_plays(Some(1)).repeat);
+		assert_eq!(-1, anim_settings.with_plays(Some(0)).repeat);
+		assert_eq!(-2, anim_settings.with_plays(None).repeat);
 	}
 	#[test]
 	fn test_split_settings() {
@@ -293,5 +588,20 @@ mod tests {
 		let split_settings = SplitSettings::from_parser(ArgParser::from_args(&args));
 		assert_eq!(PathBuf::from("x"), split_settings.file);
 		assert_eq!(Some(OsStr::new("x_frames")), split_settings.dir.file_name());
+		assert_eq!(None, split_settings.sheet);
+		let args = App::new("test")
+			.arg(Arg::with_name("file").required(true))
+			.arg(Arg::with_name("sheet").long("sheet").takes_value(true))
+			.arg(Arg::with_name("columns").long("columns").takes_value(true))
+			.get_matches_from(vec![
+				"test",
+				"x",
+				"--sheet",
+				"grid",
+				"--columns",
+				"4",
+			]);
+		let split_settings = SplitSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(Some((SheetLayout::Grid, 4)), split_settings.sheet);
 	}
 }
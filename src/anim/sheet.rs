@@ -0,0 +1,465 @@
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use image::ExtendedColorType;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/* Layout of the frames in the sprite sheet */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SheetLayout {
+	Grid,
+	Packed,
+}
+
+/* Implementation for parsing SheetLayout from a string */
+impl FromStr for SheetLayout {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"grid" => Ok(Self::Grid),
+			"packed" => Ok(Self::Packed),
+			_ => Err("Unrecognized layout"),
+		}
+	}
+}
+
+/* Frame entry of the JSON atlas */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct AtlasFrame {
+	x: u32,
+	y: u32,
+	w: u32,
+	h: u32,
+	#[serde(default)]
+	left: u32,
+	#[serde(default)]
+	top: u32,
+	duration: Option<u32>,
+}
+
+/* Frame rectangle in the sprite sheet */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "AtlasFrame", into = "AtlasFrame")]
+pub struct SheetFrame {
+	pub rect: Geometry,
+	pub left: u32,
+	pub top: u32,
+	pub duration: Option<u32>,
+}
+
+impl SheetFrame {
+	/**
+	 * Create a new SheetFrame object.
+	 *
+	 * @param  rect
+	 * @param  left
+	 * @param  top
+	 * @param  duration (Option)
+	 * @return SheetFrame
+	 */
+	pub fn new(rect: Geometry, left: u32, top: u32, duration: Option<u32>) -> Self {
+		Self {
+			rect,
+			left,
+			top,
+			duration,
+		}
+	}
+}
+
+/* Implementation for converting the atlas entry to SheetFrame */
+impl From<AtlasFrame> for SheetFrame {
+	fn from(frame: AtlasFrame) -> Self {
+		Self::new(
+			Geometry::new(frame.x as i32, frame.y as i32, frame.w, frame.h),
+			frame.left,
+			frame.top,
+			frame.duration,
+		)
+	}
+}
+
+/* Implementation for converting SheetFrame to the atlas entry */
+impl From<SheetFrame> for AtlasFrame {
+	fn from(frame: SheetFrame) -> Self {
+		Self {
+			x: frame.rect.x as u32,
+			y: frame.rect.y as u32,
+			w: frame.rect.width,
+			h: frame.rect.height,
+			left: frame.left,
+			top: frame.top,
+			duration: frame.duration,
+		}
+	}
+}
+
+/* Sprite sheet (atlas) of the animation frames */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheet {
+	#[serde(rename = "image")]
+	pub file: PathBuf,
+	#[serde(default)]
+	pub width: u32,
+	#[serde(default)]
+	pub height: u32,
+	#[serde(default)]
+	pub plays: u32,
+	pub frames: Vec<SheetFrame>,
+}
+
+impl SpriteSheet {
+	/**
+	 * Create a new SpriteSheet object.
+	 *
+	 * @param  file
+	 * @param  width
+	 * @param  height
+	 * @param  plays
+	 * @param  frames
+	 * @return SpriteSheet
+	 */
+	pub fn new(
+		file: PathBuf,
+		width: u32,
+		height: u32,
+		plays: u32,
+		frames: Vec<SheetFrame>,
+	) -> Self {
+		Self {
+			file,
+			width,
+			height,
+			plays,
+			frames,
+		}
+	}
+
+	/**
+	 * Create a SpriteSheet object from the grid dimensions.
+	 *
+	 * @param  file
+	 * @param  size
+	 * @param  grid
+	 * @return SpriteSheet
+	 */
+	pub fn from_grid(file: PathBuf, size: (u32, u32), grid: (u32, u32)) -> Self {
+		let (columns, rows) = (grid.0.max(1), grid.1.max(1));
+		let (width, height) = (size.0 / columns, size.1 / rows);
+		Self::new(
+			file,
+			width,
+			height,
+			0,
+			(0..rows)
+				.flat_map(|row| {
+					(0..columns).map(move |column| {
+						SheetFrame::new(
+							Geometry::new(
+								(column * width) as i32,
+								(row * height) as i32,
+								width,
+								height,
+							),
+							0,
+							0,
+							None,
+						)
+					})
+				})
+				.collect(),
+		)
+	}
+
+	/**
+	 * Parse a sprite sheet from the JSON atlas.
+	 *
+	 * The image file is resolved relative to the given directory.
+	 *
+	 * @param  value
+	 * @param  dir
+	 * @return SpriteSheet (Option)
+	 */
+	pub fn parse(value: &str, dir: &Path) -> Option<Self> {
+		let sheet = serde_json::from_str::<Self>(value).ok()?;
+		let size = sheet.frames.iter().fold((0, 0), |size, frame| {
+			(
+				size.0.max(frame.left + frame.rect.width),
+				size.1.max(frame.top + frame.rect.height),
+			)
+		});
+		Some(Self {
+			file: dir.join(&sheet.file),
+			width: match sheet.width {
+				0 => size.0,
+				width => width,
+			},
+			height: match sheet.height {
+				0 => size.1,
+				height => height,
+			},
+			..sheet
+		})
+	}
+
+	/**
+	 * Get the sprite sheet as JSON atlas.
+	 *
+	 * @return String
+	 */
+	pub fn get_json(&self) -> String {
+		serde_json::to_string_pretty(self)
+			.expect("Failed to serialize the sprite sheet")
+			+ "\n"
+	}
+
+	/**
+	 * Copy a rectangle of pixels between the RGBA buffers.
+	 *
+	 * @param  source
+	 * @param  source_width
+	 * @param  rect
+	 * @param  target
+	 * @param  target_width
+	 * @param  position
+	 */
+	fn copy_rect(
+		source: &[u8],
+		source_width: u32,
+		rect: Geometry,
+		target: &mut [u8],
+		target_width: u32,
+		position: (u32, u32),
+	) {
+		let len = (rect.width * 4) as usize;
+		for row in 0..rect.height {
+			let from = (((rect.y as u32 + row) * source_width + rect.x as u32) * 4)
+				as usize;
+			let to = (((position.1 + row) * target_width + position.0) * 4) as usize;
+			if from + len <= source.len() && to + len <= target.len() {
+				target[to..to + len].copy_from_slice(&source[from..from + len]);
+			}
+		}
+	}
+
+	/**
+	 * Pack the images into a sprite sheet.
+	 *
+	 * Packed layout trims the transparent borders of the frames
+	 * and places them in rows from the tallest to the shortest.
+	 *
+	 * @param  images
+	 * @param  fps
+	 * @param  layout
+	 * @param  columns
+	 * @return Tuple (Image, Vector of SheetFrame)
+	 */
+	pub fn pack(
+		images: &[Image],
+		fps: u32,
+		layout: SheetLayout,
+		columns: u32,
+	) -> (Image, Vec<SheetFrame>) {
+		let data = images
+			.iter()
+			.map(|image| image.get_data(ExtendedColorType::Rgba8))
+			.collect::<Vec<Vec<u8>>>();
+		let rects = images
+			.iter()
+			.zip(&data)
+			.map(|(image, data)| match layout {
+				SheetLayout::Grid => {
+					Geometry::new(0, 0, image.geometry.width, image.geometry.height)
+				}
+				SheetLayout::Packed => Geometry::get_bounds(
+					image.geometry.width,
+					image.geometry.height,
+					|i| data[i + 3] != 0,
+				)
+				.unwrap_or_default(),
+			})
+			.collect::<Vec<Geometry>>();
+		let mut positions = vec![(0, 0); images.len()];
+		let (mut width, mut height) = (0, 0);
+		match layout {
+			SheetLayout::Grid => {
+				let columns = match columns {
+					0 => (images.len() as f64).sqrt().ceil() as u32,
+					columns => columns,
+				}
+				.max(1);
+				let cell = rects.iter().fold((0, 0), |cell, rect| {
+					(cell.0.max(rect.width), cell.1.max(rect.height))
+				});
+				for (i, position) in positions.iter_mut().enumerate() {
+					let (column, row) = (i as u32 % columns, i as u32 / columns);
+					*position = (column * cell.0, row * cell.1);
+					width = width.max((column + 1) * cell.0);
+					height = height.max((row + 1) * cell.1);
+				}
+			}
+			SheetLayout::Packed => {
+				let area = rects
+					.iter()
+					.map(|rect| u64::from(rect.width) * u64::from(rect.height))
+					.sum::<u64>();
+				let max_width = rects
+					.iter()
+					.map(|rect| rect.width)
+					.max()
+					.unwrap_or_default();
+				let sheet_width = max_width.max((area as f64).sqrt().ceil() as u32);
+				let mut order = (0..rects.len()).collect::<Vec<usize>>();
+				order.sort_by(|a, b| rects[*b].height.cmp(&rects[*a].height));
+				let (mut x, mut y, mut shelf) = (0, 0, 0);
+				for i in order {
+					if x + rects[i].width > sheet_width {
+						x = 0;
+						y += shelf;
+						shelf = 0;
+					}
+					positions[i] = (x, y);
+					x += rects[i].width;
+					shelf = shelf.max(rects[i].height);
+					width = width.max(x);
+				}
+				height = y + shelf;
+			}
+		}
+		let mut pixels = vec![0; (width * height * 4) as usize];
+		let mut frames = Vec::new();
+		for (i, image) in images.iter().enumerate() {
+			Self::copy_rect(
+				&data[i],
+				image.geometry.width,
+				rects[i],
+				&mut pixels,
+				width,
+				positions[i],
+			);
+			frames.push(SheetFrame::new(
+				Geometry::new(
+					positions[i].0 as i32,
+					positions[i].1 as i32,
+					rects[i].width,
+					rects[i].height,
+				),
+				rects[i].x as u32,
+				rects[i].y as u32,
+				Some(image.delay.unwrap_or_else(|| 1000 / fps.max(1))),
+			));
+		}
+		(
+			Image::from_rgba(&pixels, Geometry::new(0, 0, width, height)),
+			frames,
+		)
+	}
+
+	/**
+	 * Cut the frames from the sprite sheet image.
+	 *
+	 * @param  sheet
+	 * @return Vector of Image
+	 */
+	pub fn get_images(&self, sheet: &Image) -> Vec<Image> {
+		let data = sheet.get_data(ExtendedColorType::Rgba8);
+		let canvas = Geometry::new(0, 0, self.width, self.height);
+		self.frames
+			.iter()
+			.map(|frame| {
+				let mut pixels =
+					vec![0; (canvas.width * canvas.height * 4) as usize];
+				let rect = Geometry::new(
+					frame.rect.x,
+					frame.rect.y,
+					frame
+						.rect
+						.width
+						.min(canvas.width.saturating_sub(frame.left)),
+					frame
+						.rect
+						.height
+						.min(canvas.height.saturating_sub(frame.top)),
+				);
+				Self::copy_rect(
+					&data,
+					sheet.geometry.width,
+					rect,
+					&mut pixels,
+					canvas.width,
+					(frame.left, frame.top),
+				);
+				Image::from_rgba(&pixels, canvas).with_delay(frame.duration)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Bgra;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_sprite_sheet() {
+		assert_eq!(Ok(SheetLayout::Packed), SheetLayout::from_str("packed"));
+		assert!(SheetLayout::from_str("x").is_err());
+		let mut images = Vec::new();
+		for (i, x) in [0, 2, 1].iter().enumerate() {
+			let mut image = Image::new(
+				vec![Bgra::from([0, 0, 0, 0]); 16],
+				true,
+				Geometry::new(0, 0, 4, 4),
+			);
+			image.set_pixel(*x, 1, Bgra::from([255, 0, 0, 255]));
+			image.set_pixel(*x + 1, 2, Bgra::from([0, 255, 0, 255]));
+			images.push(image.with_delay(Some(100 * (i as u32 + 1))));
+		}
+		let (sheet, frames) = SpriteSheet::pack(&images, 10, SheetLayout::Grid, 2);
+		assert_eq!(Geometry::new(0, 0, 8, 8), sheet.geometry);
+		assert_eq!(Geometry::new(0, 4, 4, 4), frames[2].rect);
+		let atlas = SpriteSheet::new(PathBuf::from("sheet.png"), 4, 4, 2, frames);
+		assert_eq!(
+			Some(atlas.clone()),
+			SpriteSheet::parse(&atlas.get_json(), Path::new(""))
+		);
+		assert_eq!(
+			Some(SpriteSheet::new(
+				PathBuf::from("frames/sheet.png"),
+				3,
+				2,
+				0,
+				vec![SheetFrame::new(Geometry::new(1, 0, 2, 2), 1, 0, None)]
+			)),
+			SpriteSheet::parse(
+				r#"{"image": "sheet.png", "frames": [{"x": 1, "y": 0, "w": 2, "h": 2, "left": 1}]}"#,
+				Path::new("frames")
+			)
+		);
+		assert_eq!(None, SpriteSheet::parse("{\"frames\": []}", Path::new("")));
+		let (sheet, frames) = SpriteSheet::pack(&images, 10, SheetLayout::Packed, 0);
+		assert_eq!(Geometry::new(0, 0, 4, 4), sheet.geometry);
+		assert_eq!(
+			SheetFrame::new(Geometry::new(2, 0, 2, 2), 2, 1, Some(200)),
+			frames[1]
+		);
+		let atlas = SpriteSheet::new(PathBuf::new(), 4, 4, 0, frames);
+		for (image, frame) in images.iter().zip(atlas.get_images(&sheet)) {
+			assert_eq!(image.delay, frame.delay);
+			assert_eq!(
+				image.get_data(ExtendedColorType::Rgba8),
+				frame.get_data(ExtendedColorType::Rgba8)
+			);
+		}
+		let grid = SpriteSheet::from_grid(PathBuf::new(), (8, 8), (2, 2));
+		assert_eq!(4, grid.frames.len());
+		assert_eq!((4, 4), (grid.width, grid.height));
+		assert_eq!(Geometry::new(4, 4, 4, 4), grid.frames[3].rect);
+		let sheet = SpriteSheet::pack(&images, 10, SheetLayout::Grid, 2).0;
+		assert_eq!(
+			images[2].get_data(ExtendedColorType::Rgba8),
+			grid.get_images(&sheet)[2].get_data(ExtendedColorType::Rgba8)
+		);
+	}
+}
@@ -0,0 +1,257 @@
+use crate::anim::settings::AnimSettings;
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use image::ExtendedColorType;
+use std::str::FromStr;
+
+/* Transition effect between the images */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+	Crossfade,
+	Slide,
+	Wipe,
+}
+
+/* Implementation for parsing Transition from a string */
+impl FromStr for Transition {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"crossfade" => Ok(Self::Crossfade),
+			"slide" => Ok(Self::Slide),
+			"wipe" => Ok(Self::Wipe),
+			_ => Err("Unrecognized transition"),
+		}
+	}
+}
+
+impl Transition {
+	/**
+	 * Get the RGBA pixels of a transition frame.
+	 *
+	 * @param  first
+	 * @param  second
+	 * @param  width
+	 * @param  progress
+	 * @return Vector of u8
+	 */
+	pub fn get_frame(
+		&self,
+		first: &[u8],
+		second: &[u8],
+		width: usize,
+		progress: f64,
+	) -> Vec<u8> {
+		let offset = ((progress * width as f64).round() as usize).min(width) * 4;
+		match self {
+			Self::Crossfade => first
+				.iter()
+				.zip(second)
+				.map(|(a, b)| {
+					(f64::from(*a) * (1. - progress) + f64::from(*b) * progress)
+						.round() as u8
+				})
+				.collect(),
+			Self::Slide => first
+				.chunks(width * 4)
+				.zip(second.chunks(width * 4))
+				.flat_map(|(a, b)| a[offset..].iter().chain(&b[..offset]).copied())
+				.collect(),
+			Self::Wipe => first
+				.chunks(width * 4)
+				.zip(second.chunks(width * 4))
+				.flat_map(|(a, b)| b[..offset].iter().chain(&a[offset..]).copied())
+				.collect(),
+		}
+	}
+}
+
+/* Slideshow maker with hold durations and transitions */
+#[derive(Debug)]
+pub struct Slideshow<'a> {
+	settings: &'a AnimSettings,
+}
+
+impl<'a> Slideshow<'a> {
+	/**
+	 * Create a new Slideshow object.
+	 *
+	 * @param  settings
+	 * @return Slideshow
+	 */
+	pub fn new(settings: &'a AnimSettings) -> Self {
+		Self { settings }
+	}
+
+	/**
+	 * Get the RGBA pixels of the image centered on the canvas.
+	 *
+	 * @param  image
+	 * @param  canvas
+	 * @return Vector of u8
+	 */
+	fn get_canvas(image: &Image, canvas: Geometry) -> Vec<u8> {
+		let data = image.get_data(ExtendedColorType::Rgba8);
+		let (width, height) = (
+			image.geometry.width as usize,
+			image.geometry.height as usize,
+		);
+		if (width, height) == (canvas.width as usize, canvas.height as usize) {
+			return data;
+		}
+		let mut pixels = vec![0; (canvas.width * canvas.height * 4) as usize];
+		let (x, y) = (
+			(canvas.width as usize - width) / 2,
+			(canvas.height as usize - height) / 2,
+		);
+		for (row, line) in data.chunks(width * 4).enumerate() {
+			let start = ((y + row) * canvas.width as usize + x) * 4;
+			pixels[start..start + line.len()].copy_from_slice(line);
+		}
+		pixels
+	}
+
+	/**
+	 * Get the frames of the slideshow.
+	 *
+	 * @param  images
+	 * @return Vector of Image
+	 */
+	pub fn get_frames(&self, images: Vec<Image>) -> Vec<Image> {
+		let canvas = Geometry::new(
+			0,
+			0,
+			images
+				.iter()
+				.map(|image| image.geometry.width)
+				.max()
+				.unwrap_or(0),
+			images
+				.iter()
+				.map(|image| image.geometry.height)
+				.max()
+				.unwrap_or(0),
+		);
+		if self.settings.hold.is_empty()
+			&& self.settings.transition.is_none()
+			&& images.iter().all(|image| {
+				(image.geometry.width, image.geometry.height)
+					== (canvas.width, canvas.height)
+			}) {
+			return images;
+		}
+		let pixels = images
+			.iter()
+			.map(|image| Self::get_canvas(image, canvas))
+			.collect::<Vec<Vec<u8>>>();
+		let mut frames = Vec::new();
+		for (i, data) in pixels.iter().enumerate() {
+			frames.push(
+				Image::from_rgba(data, canvas).with_delay(
+					self.settings
+						.hold
+						.get(i)
+						.or_else(|| self.settings.hold.last())
+						.copied(),
+				),
+			);
+			if let (Some((transition, duration)), Some(next)) =
+				(self.settings.transition, pixels.get(i + 1))
+			{
+				let count = ((f64::from(duration * self.settings.fps) / 1e3).round()
+					as u32)
+					.max(1);
+				for frame in 0..count {
+					let progress = f64::from(frame + 1) / f64::from(count + 1);
+					frames.push(
+						Image::from_rgba(
+							&transition.get_frame(
+								data,
+								next,
+								canvas.width as usize,
+								progress,
+							),
+							canvas,
+						)
+						.with_delay(Some(
+							(duration * (frame + 1)) / count
+								- (duration * frame) / count,
+						)),
+					);
+				}
+			}
+		}
+		frames
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Bgra;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_slideshow() {
+		assert_eq!(Ok(Transition::Wipe), Transition::from_str("wipe"));
+		assert!(Transition::from_str("x").is_err());
+		let (first, second) = ([0; 8], [255; 8]);
+		assert_eq!(
+			vec![0, 0, 0, 0, 255, 255, 255, 255],
+			Transition::Slide.get_frame(&first, &second, 2, 0.5)
+		);
+		assert_eq!(
+			vec![255, 255, 255, 255, 0, 0, 0, 0],
+			Transition::Wipe.get_frame(&first, &second, 2, 0.5)
+		);
+		assert_eq!(
+			vec![64; 8],
+			Transition::Crossfade.get_frame(&first, &second, 2, 0.25)
+		);
+		let settings = AnimSettings {
+			fps: 10,
+			hold: vec![1000, 500],
+			transition: Some((Transition::Crossfade, 300)),
+			..AnimSettings::default()
+		};
+		let images = vec![
+			Image::new(
+				vec![Bgra::from([0, 0, 0, 255]); 4],
+				false,
+				Geometry::new(0, 0, 2, 2),
+			),
+			Image::new(
+				vec![Bgra::from([255, 255, 255, 255]); 2],
+				false,
+				Geometry::new(0, 0, 1, 2),
+			),
+			Image::new(
+				vec![Bgra::from([255, 255, 255, 255]); 4],
+				false,
+				Geometry::new(0, 0, 2, 2),
+			),
+		];
+		let frames = Slideshow::new(&settings).get_frames(images);
+		assert_eq!(9, frames.len());
+		assert_eq!(
+			vec![
+				Some(1000),
+				Some(100),
+				Some(100),
+				Some(100),
+				Some(500),
+				Some(100),
+				Some(100),
+				Some(100),
+				Some(500)
+			],
+			frames.iter().map(|frame| frame.delay).collect::<Vec<_>>()
+		);
+		assert!(frames
+			.iter()
+			.all(|frame| frame.geometry == Geometry::new(0, 0, 2, 2)));
+		assert_eq!(
+			vec![255, 255, 255, 255, 0, 0, 0, 0],
+			frames[4].get_data(ExtendedColorType::Rgba8)[..8].to_vec()
+		);
+	}
+}
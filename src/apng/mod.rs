@@ -1,19 +1,23 @@
+pub mod writer;
+
 use crate::anim::settings::AnimSettings;
+use crate::apng::writer::ApngWriter;
 use crate::image::geometry::Geometry;
+use crate::image::settings::PngSettings;
 use crate::image::Image;
 use crate::util::state::InputState;
-use apng::{Config, Encoder, Frame, PNGImage};
+use apng::{BlendOp, DisposeOp, Frame};
 use image::ExtendedColorType;
-use png::{BitDepth, ColorType, FilterType};
 use std::convert::TryInto;
 use std::io::{self, Write};
 
 /* APNG encoder and settings */
 #[derive(Debug)]
 pub struct ApngEncoder<'a> {
+	frame_count: u32,
 	geometry: Geometry,
-	config: Config,
 	settings: &'a AnimSettings,
+	png: &'a PngSettings,
 }
 
 impl<'a> ApngEncoder<'a> {
@@ -23,26 +27,85 @@ impl<'a> ApngEncoder<'a> {
 	 * @param  frame_count
 	 * @param  geometry
 	 * @param  settings
+	 * @param  png
 	 * @return ApngEncoder
 	 */
 	pub fn new(
 		frame_count: u32,
 		geometry: Geometry,
 		settings: &'a AnimSettings,
+		png: &'a PngSettings,
 	) -> Self {
 		Self {
+			frame_count,
 			geometry,
-			config: Config {
-				width: geometry.width,
-				height: geometry.height,
-				num_frames: frame_count,
-				num_plays: settings.repeat.try_into().unwrap_or_default(),
-				color: ColorType::RGBA,
-				depth: BitDepth::Eight,
-				filter: FilterType::NoFilter,
-			},
 			settings,
+			png,
+		}
+	}
+
+	/**
+	 * Get the changed area of the frame and its pixels to blend.
+	 *
+	 * Unchanged pixels are left transparent when both frames are opaque
+	 * in the changed area, otherwise the area replaces the previous pixels.
+	 *
+	 * @param  data
+	 * @param  previous
+	 * @return Tuple (Geometry, Vector of u8, BlendOp)
+	 */
+	fn get_delta(
+		&self,
+		data: &[u8],
+		previous: &[u8],
+	) -> (Geometry, Vec<u8>, BlendOp) {
+		let width = self.geometry.width as usize;
+		let area = match Geometry::get_bounds(
+			self.geometry.width,
+			self.geometry.height,
+			|i| data[i..i + 4] != previous[i..i + 4],
+		) {
+			Some(area) => area,
+			None => {
+				return (
+					Geometry::new(0, 0, 1, 1),
+					vec![0; 4],
+					BlendOp::ApngBlendOpOver,
+				)
+			}
+		};
+		let (x1, y1) = (area.x as usize, area.y as usize);
+		let (x2, y2) = (x1 + area.width as usize - 1, y1 + area.height as usize - 1);
+		let rows = (y1..=y2).map(|y| (y * width + x1) * 4..(y * width + x2 + 1) * 4);
+		let opaque = rows.clone().all(|row| {
+			data[row.clone()].chunks(4).all(|pixel| pixel[3] == 255)
+				&& previous[row].chunks(4).all(|pixel| pixel[3] == 255)
+		});
+		let mut delta = Vec::with_capacity((area.width * area.height * 4) as usize);
+		for row in rows {
+			if opaque {
+				for (pixel, previous_pixel) in
+					data[row.clone()].chunks(4).zip(previous[row].chunks(4))
+				{
+					delta.extend_from_slice(if pixel == previous_pixel {
+						&[0; 4]
+					} else {
+						pixel
+					});
+				}
+			} else {
+				delta.extend_from_slice(&data[row]);
+			}
 		}
+		(
+			area,
+			delta,
+			if opaque {
+				BlendOp::ApngBlendOpOver
+			} else {
+				BlendOp::ApngBlendOpSource
+			},
+		)
 	}
 
 	/**
@@ -55,10 +118,21 @@ impl<'a> ApngEncoder<'a> {
 		&self,
 		images: Vec<Image>,
 		input_state: Option<&'static InputState>,
-		mut output: Output,
+		output: Output,
 	) {
-		let mut encoder = Encoder::new(&mut output, self.config.clone())
-			.expect("Failed to create APNG encoder");
+		let mut writer = ApngWriter::new(
+			output,
+			(self.geometry.width, self.geometry.height),
+			self.frame_count,
+			self.settings
+				.repeat
+				.checked_add(1)
+				.and_then(|plays| plays.try_into().ok())
+				.unwrap_or_default(),
+			self.png,
+		)
+		.expect("Failed to create APNG encoder");
+		let mut previous: Option<Vec<u8>> = None;
 		for (i, image) in images.iter().enumerate() {
 			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
 			info!("Saving... ({:.1}%)\r", percentage);
@@ -76,50 +150,103 @@ impl<'a> ApngEncoder<'a> {
 					panic!("Failed to write the frames")
 				}
 			}
-			encoder
+			let data = image.get_data(ExtendedColorType::Rgba8);
+			let (geometry, delta, blend_op) = match previous {
+				Some(ref previous) => self.get_delta(&data, previous),
+				None => (self.geometry, data.clone(), BlendOp::ApngBlendOpSource),
+			};
+			writer
 				.write_frame(
-					&PNGImage {
-						width: self.geometry.width,
-						height: self.geometry.height,
-						data: image.get_data(ExtendedColorType::Rgba8),
-						color_type: self.config.color,
-						bit_depth: self.config.depth,
-					},
-					Frame {
-						delay_num: Some(1),
-						delay_den: Some(self.settings.fps.try_into().unwrap_or(1)),
-						..Default::default()
+					&delta,
+					&Frame {
+						width: Some(geometry.width),
+						height: Some(geometry.height),
+						offset_x: geometry.x.try_into().ok(),
+						offset_y: geometry.y.try_into().ok(),
+						delay_num: Some(
+							image.delay.map_or(1, |delay| {
+								delay.min(u16::MAX.into()) as u16
+							}),
+						),
+						delay_den: Some(image.delay.map_or(
+							self.settings.fps.try_into().unwrap_or(1),
+							|_| 1000,
+						)),
+						dispose_op: Some(DisposeOp::ApngDisposeOpNone),
+						blend_op: Some(blend_op),
 					},
 				)
 				.unwrap_or_else(|_| {
 					panic!("Failed to write frame: {}/{}", i + 1, images.len())
 				});
+			previous = Some(data);
 		}
 		info!("\n");
-		encoder.finish_encode().expect("Failed to finish encoding");
+		writer.finish().expect("Failed to finish encoding");
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use image::Bgra;
+	use crate::window::pattern::{Pattern, PatternWindow};
+	use image::codecs::png::PngDecoder;
+	use image::{AnimationDecoder, ImageDecoder};
+	use pretty_assertions::assert_eq;
 	#[test]
 	fn test_apng_encoder() {
-		let geometry = Geometry::new(0, 0, 1, 2);
-		let data = vec![Bgra::from([128, 128, 128, 0]), Bgra::from([16, 16, 16, 0])];
-		let images = vec![
-			Image::new(data.clone(), false, geometry),
-			Image::new(data.into_iter().rev().collect(), false, geometry),
-		];
+		let (settings, png) = (AnimSettings::default(), PngSettings::default());
+		let geometry = Geometry::new(0, 0, 32, 24);
+		let window = PatternWindow::new(Pattern::Boxes, geometry);
+		let images: Vec<Image> =
+			(0..3).map(|frame| window.get_frame(frame)).collect();
 		let mut output = Vec::new();
 		ApngEncoder::new(
 			images.len().try_into().unwrap(),
 			geometry,
-			&AnimSettings::default(),
+			&settings,
+			&png,
 		)
-		.save(images, None, &mut output);
+		.save(images.clone(), None, &mut output);
+		assert_eq!(
+			(32, 24),
+			PngDecoder::new(output.as_slice()).unwrap().dimensions()
+		);
+		let frames = PngDecoder::new(output.as_slice())
+			.unwrap()
+			.apng()
+			.into_frames()
+			.collect_frames()
+			.unwrap();
+		for (frame, image) in frames.iter().zip(images) {
+			assert_eq!(
+				image.get_data(ExtendedColorType::Rgba8),
+				frame.buffer().to_vec()
+			);
+		}
 		output.truncate(6);
 		assert_eq!(vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a], output);
+		let encoder =
+			ApngEncoder::new(2, Geometry::new(0, 0, 2, 2), &settings, &png);
+		let previous = [255; 16];
+		let mut data = previous;
+		data[12..16].copy_from_slice(&[0, 0, 0, 255]);
+		assert_eq!(
+			(
+				Geometry::new(1, 1, 1, 1),
+				vec![0, 0, 0, 255],
+				BlendOp::ApngBlendOpOver
+			),
+			encoder.get_delta(&data, &previous)
+		);
+		data[15] = 0;
+		assert_eq!(
+			BlendOp::ApngBlendOpSource,
+			encoder.get_delta(&data, &previous).2
+		);
+		assert_eq!(
+			Geometry::new(0, 0, 1, 1),
+			encoder.get_delta(&previous, &previous).0
+		);
 	}
 }
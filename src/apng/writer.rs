@@ -0,0 +1,202 @@
+use crate::image::settings::PngSettings;
+use apng::{BlendOp, DisposeOp, Frame};
+use crc32fast::Hasher;
+use image::png::PngEncoder;
+use image::ColorType;
+use std::convert::TryInto;
+use std::io::{Result, Write};
+
+/* Signature of the PNG files */
+const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/* APNG writer that supports frames smaller than the canvas */
+pub struct ApngWriter<'a, Output: Write> {
+	output: Output,
+	settings: &'a PngSettings,
+	sequence: u32,
+	frame_index: u32,
+}
+
+impl<'a, Output: Write> ApngWriter<'a, Output> {
+	/**
+	 * Create a new ApngWriter object and write the header chunks.
+	 *
+	 * @param  output
+	 * @param  size
+	 * @param  frame_count
+	 * @param  plays
+	 * @param  settings
+	 * @return ApngWriter (Result)
+	 */
+	pub fn new(
+		mut output: Output,
+		size: (u32, u32),
+		frame_count: u32,
+		plays: u32,
+		settings: &'a PngSettings,
+	) -> Result<Self> {
+		output.write_all(&PNG_SIGNATURE)?;
+		let mut writer = Self {
+			output,
+			settings,
+			sequence: 0,
+			frame_index: 0,
+		};
+		let mut header = Vec::new();
+		header.extend_from_slice(&size.0.to_be_bytes());
+		header.extend_from_slice(&size.1.to_be_bytes());
+		header.extend_from_slice(&[8, 6, 0, 0, 0]);
+		writer.write_chunk(b"IHDR", &header)?;
+		let mut control = Vec::new();
+		control.extend_from_slice(&frame_count.to_be_bytes());
+		control.extend_from_slice(&plays.to_be_bytes());
+		writer.write_chunk(b"acTL", &control)?;
+		Ok(writer)
+	}
+
+	/**
+	 * Write a chunk with its length and checksum.
+	 *
+	 * @param  chunk_type
+	 * @param  data
+	 * @return Result
+	 */
+	fn write_chunk(&mut self, chunk_type: &[u8; 4], data: &[u8]) -> Result<()> {
+		let length: u32 = data.len().try_into().unwrap_or_default();
+		let mut hasher = Hasher::new();
+		hasher.update(chunk_type);
+		hasher.update(data);
+		self.output.write_all(&length.to_be_bytes())?;
+		self.output.write_all(chunk_type)?;
+		self.output.write_all(data)?;
+		self.output.write_all(&hasher.finalize().to_be_bytes())
+	}
+
+	/**
+	 * Get the compressed image data of the RGBA pixels.
+	 *
+	 * @param  data
+	 * @param  width
+	 * @param  height
+	 * @return Vector of u8
+	 */
+	fn get_image_data(&self, data: &[u8], width: u32, height: u32) -> Vec<u8> {
+		let mut png = Vec::new();
+		PngEncoder::new_with_quality(
+			&mut png,
+			self.settings.compression,
+			self.settings.filter,
+		)
+		.encode(data, width, height, ColorType::Rgba8)
+		.expect("Failed to encode the frame");
+		let mut image_data = Vec::new();
+		let mut chunks = &png[PNG_SIGNATURE.len()..];
+		while chunks.len() >= 12 {
+			let length = u32::from_be_bytes(
+				chunks[..4].try_into().expect("Invalid chunk length"),
+			) as usize;
+			if &chunks[4..8] == b"IDAT" {
+				image_data.extend_from_slice(&chunks[8..8 + length]);
+			}
+			chunks = &chunks[12 + length..];
+		}
+		image_data
+	}
+
+	/**
+	 * Write the frame with its control chunk.
+	 *
+	 * @param  data
+	 * @param  frame
+	 * @return Result
+	 */
+	pub fn write_frame(&mut self, data: &[u8], frame: &Frame) -> Result<()> {
+		let (width, height) = (frame.width.unwrap_or(1), frame.height.unwrap_or(1));
+		let mut control = Vec::new();
+		control.extend_from_slice(&self.sequence.to_be_bytes());
+		control.extend_from_slice(&width.to_be_bytes());
+		control.extend_from_slice(&height.to_be_bytes());
+		control.extend_from_slice(&frame.offset_x.unwrap_or(0).to_be_bytes());
+		control.extend_from_slice(&frame.offset_y.unwrap_or(0).to_be_bytes());
+		control.extend_from_slice(&frame.delay_num.unwrap_or(1).to_be_bytes());
+		control.extend_from_slice(&frame.delay_den.unwrap_or(30).to_be_bytes());
+		control.push(frame.dispose_op.unwrap_or(DisposeOp::ApngDisposeOpNone) as u8);
+		control.push(frame.blend_op.unwrap_or(BlendOp::ApngBlendOpSource) as u8);
+		self.write_chunk(b"fcTL", &control)?;
+		self.sequence += 1;
+		let image_data = self.get_image_data(data, width, height);
+		if self.frame_index == 0 {
+			self.write_chunk(b"IDAT", &image_data)?;
+		} else {
+			let mut frame_data = self.sequence.to_be_bytes().to_vec();
+			frame_data.extend_from_slice(&image_data);
+			self.write_chunk(b"fdAT", &frame_data)?;
+			self.sequence += 1;
+		}
+		self.frame_index += 1;
+		Ok(())
+	}
+
+	/**
+	 * Write the end chunk.
+	 *
+	 * @return Result
+	 */
+	pub fn finish(mut self) -> Result<()> {
+		self.write_chunk(b"IEND", &[])?;
+		self.output.flush()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::codecs::png::PngDecoder;
+	use image::AnimationDecoder;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_apng_writer() {
+		let mut output = Vec::new();
+		let settings = PngSettings::default();
+		let mut writer =
+			ApngWriter::new(&mut output, (4, 4), 2, 3, &settings).unwrap();
+		writer
+			.write_frame(
+				&[255; 64],
+				&Frame {
+					width: Some(4),
+					height: Some(4),
+					delay_num: Some(100),
+					delay_den: Some(1000),
+					..Default::default()
+				},
+			)
+			.unwrap();
+		writer
+			.write_frame(
+				&[0, 0, 0, 255],
+				&Frame {
+					width: Some(1),
+					height: Some(1),
+					offset_x: Some(2),
+					offset_y: Some(1),
+					delay_num: Some(100),
+					delay_den: Some(1000),
+					blend_op: Some(BlendOp::ApngBlendOpOver),
+					..Default::default()
+				},
+			)
+			.unwrap();
+		writer.finish().unwrap();
+		let frames = PngDecoder::new(output.as_slice())
+			.unwrap()
+			.apng()
+			.into_frames()
+			.collect_frames()
+			.unwrap();
+		assert_eq!(2, frames.len());
+		assert_eq!(&[255; 4], &frames[0].buffer().get_pixel(2, 1).0);
+		assert_eq!(&[0, 0, 0, 255], &frames[1].buffer().get_pixel(2, 1).0);
+		assert_eq!(&[255; 4], &frames[1].buffer().get_pixel(1, 1).0);
+	}
+}
@@ -1,5 +1,11 @@
+use crate::anim::budget::SizeBudget;
 use crate::anim::decoder::AnimDecoder;
-use crate::anim::Frames;
+use crate::anim::manifest::{Manifest, ManifestFrame};
+use crate::anim::resample::FrameResampler;
+use crate::anim::settings::AnimSettings;
+use crate::anim::sheet::SpriteSheet;
+use crate::anim::slideshow::Slideshow;
+use crate::anim::{AnimFormat, Frames};
 use crate::apng::ApngEncoder;
 use crate::args::Args;
 use crate::file::format::FileFormat;
@@ -8,7 +14,10 @@ use crate::gif::encoder::{Encoder, EncoderConfig};
 #[cfg(feature = "ski")]
 use crate::gif::ski::GifskiEncoder;
 use crate::gif::GifEncoder;
+use crate::image::geometry::Geometry;
 use crate::image::Image;
+use crate::montage::Montage;
+use crate::optimize::AnimOptimizer;
 use crate::record::Recorder;
 use crate::settings::AppSettings;
 use crate::window::Capture;
@@ -25,17 +34,18 @@ use image::pnm::{PnmEncoder, PnmSubtype};
 use image::tga::TgaEncoder;
 use image::tiff::TiffEncoder;
 use image::{
-	AnimationDecoder, ColorType, ExtendedColorType, ImageEncoder, ImageFormat,
+	AnimationDecoder, ColorType, ExtendedColorType, ImageDecoder, ImageEncoder,
+	ImageFormat, RgbaImage,
 };
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::fs::{self, File};
-use std::io::{self, Error, Read, Write};
-use std::path::Path;
+use std::io::{self, Error, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
 use std::thread;
 
-/* Application output and result types */
-pub type AppOutput = (Option<Image>, Option<Frames>);
+/* Application output (image, frames and loop count) and result types */
+pub type AppOutput = (Option<Image>, Option<Frames>, Option<u32>);
 pub type AppResult = Result<(), Error>;
 
 /* Application and main functionalities */
@@ -72,6 +82,8 @@ where
 		if let Some(misc_args) = self.settings.args.subcommand_matches("misc") {
 			if let Some(shell) = misc_args.value_of("gen-completions") {
 				Args::gen_completions(shell, &mut io::stdout());
+			} else if misc_args.is_present("list-monitors") {
+				self.list_monitors(io::stdout())?;
 			}
 		} else if self.settings.args.is_present("split") {
 			info!("Reading frames from {:?}...", self.settings.split.file);
@@ -84,11 +96,39 @@ where
 		} else if self.settings.args.is_present("analyze") {
 			debug!("Analyzing the image... ({:?})", self.settings.analyze.file);
 			self.analyze_image()?;
-		} else if self.settings.save.file.path.to_str() == Some("-") {
-			self.save_output(self.get_app_output(), io::stdout());
+		} else if self.settings.args.is_present("optimize") {
+			info!("Optimizing {:?}...", self.settings.optimize.file);
+			self.optimize_anim(&fs::read(&self.settings.optimize.file)?)?;
+		} else if self.settings.args.is_present("pick") {
+			self.pick_colors(io::stdout())?;
+		} else if self.settings.args.is_present("daemon") {
+			self.start_daemon()?;
+		} else if self.settings.args.is_present("measure") {
+			if let Some(image) = self.measure(io::stdout())? {
+				self.save((Some(image), None, None))?;
+			}
+		} else if self.settings.args.is_present("montage") {
+			info!("Composing {} file(s)...", self.settings.montage.files.len());
+			let montage = Montage::new(&self.settings.montage);
+			self.save((Some(montage.get_image(montage.get_tiles()?)), None, None))?;
+		} else {
+			self.save(self.get_app_output())?;
+		}
+		Ok(())
+	}
+
+	/**
+	 * Save the application output to the file (or stdout).
+	 *
+	 * @param  app_output
+	 * @return Result
+	 */
+	fn save(&self, app_output: AppOutput) -> AppResult {
+		if self.settings.save.file.path.to_str() == Some("-") {
+			self.save_output(app_output, io::stdout());
 		} else {
 			self.save_output(
-				self.get_app_output(),
+				app_output,
 				File::create(&self.settings.save.file.path)?,
 			);
 			info!(
@@ -101,6 +141,153 @@ where
 		Ok(())
 	}
 
+	/**
+	 * Write the available monitors to the given output.
+	 *
+	 * @param  output
+	 * @return Result
+	 */
+	fn list_monitors<Output: Write>(&self, mut output: Output) -> AppResult {
+		#[cfg(all(unix, not(target_os = "macos")))]
+		{
+			let root_window = crate::x11::display::Display::open(None)
+				.ok_or_else(|| {
+					Error::new(ErrorKind::NotConnected, "Cannot open display")
+				})?
+				.get_root_window();
+			for (i, monitor) in root_window.get_outputs().iter().enumerate() {
+				writeln!(output, "{}: {}", i + 1, monitor)?;
+			}
+			root_window.release();
+		}
+		#[cfg(not(all(unix, not(target_os = "macos"))))]
+		writeln!(output, "Listing monitors is not supported on this platform")?;
+		Ok(())
+	}
+
+	/**
+	 * Pick colors from the screen and write them to the given output.
+	 *
+	 * @param  output
+	 * @return Result
+	 */
+	fn pick_colors<Output: Write>(&self, mut output: Output) -> AppResult {
+		debug!("{:?}", self.settings.pick);
+		#[cfg(all(unix, not(target_os = "macos")))]
+		{
+			let display =
+				crate::x11::display::Display::open(None).ok_or_else(|| {
+					Error::new(ErrorKind::NotConnected, "Cannot open display")
+				})?;
+			let colors = display.pick_colors(
+				self.settings
+					.input_state
+					.expect("Failed to get the input state"),
+				self.settings.pick.count,
+			);
+			if self.settings.pick.json {
+				writeln!(
+					output,
+					"[{}]",
+					colors
+						.iter()
+						.map(|color| color.get_json())
+						.collect::<Vec<String>>()
+						.join(", ")
+				)?;
+			} else {
+				for color in colors {
+					writeln!(output, "{}", color.get_hex())?;
+				}
+			}
+			display.get_root_window().release();
+		}
+		#[cfg(not(all(unix, not(target_os = "macos"))))]
+		writeln!(output, "Picking colors is not supported on this platform")?;
+		Ok(())
+	}
+
+	/**
+	 * Measure on the screen and write the measurement to the given output.
+	 *
+	 * @param  output
+	 * @return Image (Option) (Result)
+	 */
+	fn measure<Output: Write>(
+		&self,
+		mut output: Output,
+	) -> Result<Option<Image>, Error> {
+		#[cfg(all(unix, not(target_os = "macos")))]
+		{
+			let display =
+				crate::x11::display::Display::open(Some(self.settings.record))
+					.ok_or_else(|| {
+						Error::new(ErrorKind::NotConnected, "Cannot open display")
+					})?;
+			let measurement = display.measure(
+				self.settings
+					.input_state
+					.expect("Failed to get the input state"),
+			);
+			let root_window = display.get_root_window();
+			let image = match measurement {
+				Some(measurement) => {
+					debug!("{:?}", measurement);
+					if self.settings.save.file.path.to_str() != Some("-") {
+						writeln!(output, "{}", measurement)?;
+					}
+					if self.settings.measure.annotate {
+						info!("Capturing an image...");
+						root_window.get_image().map(|mut image| {
+							let color = self.settings.record.color;
+							measurement.annotate(
+								&mut image,
+								image::Bgra::from([
+									color as u8,
+									(color >> 8) as u8,
+									(color >> 16) as u8,
+									255,
+								]),
+								root_window.get_text_mask(&measurement.to_string()),
+							);
+							image
+						})
+					} else {
+						None
+					}
+				}
+				None => None,
+			};
+			root_window.release();
+			Ok(image)
+		}
+		#[cfg(not(all(unix, not(target_os = "macos"))))]
+		Err(Error::new(
+			ErrorKind::Other,
+			"Measuring is not supported on this platform",
+		))
+	}
+
+	/**
+	 * Start the daemon for listening the hotkeys.
+	 *
+	 * @return Result
+	 */
+	fn start_daemon(&self) -> AppResult {
+		debug!("{:?}", self.settings.daemon);
+		#[cfg(all(unix, not(target_os = "macos")))]
+		return crate::x11::daemon::Daemon::new(&self.settings.daemon)
+			.ok_or_else(|| {
+				Error::new(ErrorKind::NotConnected, "Cannot open display")
+			})?
+			.run();
+		#[cfg(not(all(unix, not(target_os = "macos"))))]
+		Err(Error::new(
+			ErrorKind::Other,
+			"Daemon is not supported on this platform",
+		))
+	}
+
 	/**
 	 * Get the application output.
 	 *
@@ -108,9 +295,18 @@ where
 	 */
 	fn get_app_output(self) -> AppOutput {
 		let output = if self.settings.save.file.format.is_animation() {
-			(None, Some(self.get_frames()))
+			if self.settings.args.is_present("edit") {
+				info!("Reading frames from {:?}...", self.settings.edit.path);
+				let (frames, plays) = self.edit_anim(
+					File::open(&self.settings.edit.path).expect("File not found"),
+					&self.settings.edit.path,
+				);
+				(None, Some(frames), plays)
+			} else {
+				(None, Some(self.get_frames()), None)
+			}
 		} else {
-			(self.get_image(), None)
+			(self.get_image(), None, None)
 		};
 		if let Some(window) = self.window {
 			window.release();
@@ -139,11 +335,36 @@ where
 	 * @return Frames
 	 */
 	fn get_frames(self) -> Frames {
-		if self.settings.args.is_present("edit") {
-			info!("Reading frames from {:?}...", self.settings.edit.path);
-			self.edit_anim(
-				File::open(&self.settings.edit.path).expect("File not found"),
-				&self.settings.edit.path,
+		if let Some(sheet) = self
+			.settings
+			.anim
+			.sheet
+			.as_ref()
+			.filter(|_| self.settings.args.is_present("make"))
+		{
+			info!("Making an animation from {:?}...", sheet.file);
+			let buffer = self.read_image(&sheet.file);
+			let images = sheet
+				.get_images(&Image::from_rgba(
+					&buffer,
+					Geometry::new(0, 0, buffer.width(), buffer.height()),
+				))
+				.into_iter()
+				.map(|image| {
+					let delay = image.delay;
+					self.edit_buffer(
+						RgbaImage::from_raw(
+							image.geometry.width,
+							image.geometry.height,
+							image.get_data(ExtendedColorType::Rgba8),
+						)
+						.expect("Failed to create the frame"),
+					)
+					.with_delay(delay)
+				})
+				.collect();
+			self.retime_frames(
+				Slideshow::new(&self.settings.anim).get_frames(images),
 			)
 		} else if self.settings.args.is_present("make") {
 			info!(
@@ -157,12 +378,36 @@ where
 				images.push(self.edit_image(path));
 			}
 			debug!("\n");
-			(images, self.settings.anim.fps)
+			if let Some(manifest) = &self.settings.anim.manifest {
+				images = manifest.apply(images);
+			}
+			self.retime_frames(
+				Slideshow::new(&self.settings.anim).get_frames(images),
+			)
 		} else {
-			(self.record(), self.settings.anim.fps)
+			self.retime_frames(self.record())
 		}
 	}
 
+	/**
+	 * Apply the animation speed to the frames.
+	 *
+	 * @param  images
+	 * @return Frames
+	 */
+	fn retime_frames(self, images: Vec<Image>) -> Frames {
+		let (fps, speed) = (self.settings.anim.fps, self.settings.anim.speed);
+		let resampler = FrameResampler::new(fps, true)
+			.with_interpolation(self.settings.anim.retime);
+		(
+			match &self.settings.anim.ramp {
+				Some(ramp) => resampler.retime(ramp.apply(images, fps, speed), 1.),
+				None => resampler.retime(images, speed),
+			},
+			fps,
+		)
+	}
+
 	/**
 	 * Capture the image of window.
 	 *
@@ -170,7 +415,7 @@ where
 	 */
 	fn capture(self) -> Option<Image> {
 		let window = self.window.expect("Failed to get the window");
-		if self.settings.record.command.is_some() {
+		let image = if self.settings.record.command.is_some() {
 			let image_thread = thread::spawn(move || {
 				window.show_countdown();
 				info!("Capturing an image...");
@@ -189,7 +434,8 @@ where
 			window.show_countdown();
 			info!("Capturing an image...");
 			window.get_image()
-		}
+		};
+		image.map(|image| self.settings.record.scale.apply(image))
 	}
 
 	/**
@@ -226,19 +472,38 @@ where
 	}
 
 	/**
-	 * Edit and return the image.
+	 * Read the image from the given path.
 	 *
 	 * @param  path
-	 * @return Image
+	 * @return RgbaImage
 	 */
-	fn edit_image(self, path: &Path) -> Image {
-		let image = Reader::open(path)
+	fn read_image(self, path: &Path) -> RgbaImage {
+		Reader::open(path)
 			.expect("File not found")
 			.with_guessed_format()
 			.expect("File format not supported")
 			.decode()
 			.expect("Failed to decode the image")
-			.to_rgba8();
+			.to_rgba8()
+	}
+
+	/**
+	 * Edit and return the image.
+	 *
+	 * @param  path
+	 * @return Image
+	 */
+	fn edit_image(self, path: &Path) -> Image {
+		self.edit_buffer(self.read_image(path))
+	}
+
+	/**
+	 * Edit and return the image buffer.
+	 *
+	 * @param  image
+	 * @return Image
+	 */
+	fn edit_buffer(self, image: RgbaImage) -> Image {
 		self.settings
 			.edit
 			.get_imageops()
@@ -268,59 +533,161 @@ where
 	}
 
 	/**
-	 * Return the updated frames after decoding the animation.
+	 * Optimize the animation and save it.
+	 *
+	 * @param  data
+	 * @return Result
+	 */
+	fn optimize_anim(self, data: &[u8]) -> AppResult {
+		let format = self.settings.optimize.get_format();
+		let (size, frames) = match format {
+			AnimFormat::Gif => {
+				let decoder =
+					GifDecoder::new(data).expect("Failed to create GIF decoder");
+				(decoder.dimensions(), decoder.into_frames().collect_frames())
+			}
+			AnimFormat::Apng => {
+				let decoder =
+					PngDecoder::new(data).expect("Failed to create PNG decoder");
+				(
+					decoder.dimensions(),
+					decoder.apng().into_frames().collect_frames(),
+				)
+			}
+		};
+		let plays = format.get_plays(data);
+		debug!("Loop count: {}", plays);
+		let mut output = Vec::new();
+		AnimOptimizer::new(size, format, &self.settings.optimize).save(
+			frames.expect("Failed to collect animation frames"),
+			plays,
+			&mut output,
+		);
+		if output.len() >= data.len() {
+			warn!("Optimized animation is not smaller, keeping the original.");
+			output = data.to_vec();
+		}
+		if self.settings.save.file.path.to_str() == Some("-") {
+			io::stdout().write_all(&output)?;
+		} else {
+			fs::write(&self.settings.save.file.path, &output)?;
+			info!(
+				"{} saved to: {:?} ({} -> {})",
+				format.to_string().to_uppercase(),
+				self.settings.save.file.path,
+				ByteSize(data.len() as u64),
+				ByteSize(output.len() as u64)
+			);
+		}
+		Ok(())
+	}
+
+	/**
+	 * Return the updated frames and the loop count after decoding the animation.
 	 *
 	 * @param  input
 	 * @param  path
-	 * @return Frames
+	 * @return Tuple (Frames, u32 (Option))
 	 */
-	fn edit_anim<Input: Read>(self, input: Input, path: &Path) -> Frames {
+	fn edit_anim<Input: Read>(
+		self,
+		mut input: Input,
+		path: &Path,
+	) -> (Frames, Option<u32>) {
+		let mut data = Vec::new();
+		input
+			.read_to_end(&mut data)
+			.expect("Failed to read the animation");
 		let format = Reader::open(path)
 			.expect("File not found")
 			.with_guessed_format()
 			.expect("File format not supported")
 			.format();
-		AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
-			.update_frames(
-				match format {
-					Some(ImageFormat::Gif) => GifDecoder::new(input)
-						.expect("Failed to create GIF decoder")
-						.into_frames()
-						.collect_frames()
-						.ok(),
-					Some(ImageFormat::Png) => PngDecoder::new(input)
-						.expect("Failed to create PNG decoder")
-						.apng()
-						.into_frames()
-						.collect_frames()
-						.ok(),
-					_ => None,
-				}
-				.expect("Failed to collect animation frames"),
-			)
+		let frames =
+			AnimDecoder::new(self.settings.edit.get_imageops(), &self.settings.anim)
+				.update_frames(
+					match format {
+						Some(ImageFormat::Gif) => GifDecoder::new(data.as_slice())
+							.expect("Failed to create GIF decoder")
+							.into_frames()
+							.collect_frames()
+							.ok(),
+						Some(ImageFormat::Png) => PngDecoder::new(data.as_slice())
+							.expect("Failed to create PNG decoder")
+							.apng()
+							.into_frames()
+							.collect_frames()
+							.ok(),
+						_ => None,
+					}
+					.expect("Failed to collect animation frames"),
+				);
+		(
+			frames,
+			AnimFormat::from_data(&data).map(|format| format.get_plays(&data)),
+		)
 	}
 
 	/**
-	 * Split animation into frames.
+	 * Split animation into frames (or sprite sheet) and write their manifest.
 	 *
 	 * @param  input
 	 * @return Frames
 	 */
 	fn split_anim<Input: Read>(self, input: Input) -> AppResult {
-		let (frames, fps) = self.edit_anim(input, &self.settings.split.file);
+		let ((frames, fps), plays) =
+			self.edit_anim(input, &self.settings.split.file);
+		let plays = plays.unwrap_or_default();
 		debug!("FPS: {}", fps);
 		fs::create_dir_all(&self.settings.split.dir)?;
-		for i in 0..frames.len() {
+		let (width, height) = frames.first().map_or((0, 0), |frame| {
+			(frame.geometry.width, frame.geometry.height)
+		});
+		if let Some((layout, columns)) = self.settings.split.sheet {
+			let (image, sheet_frames) =
+				SpriteSheet::pack(&frames, fps, layout, columns);
+			let path = FileUtil::get_path_with_extension(
+				self.settings.split.dir.join("sheet"),
+				&self.settings.save.file.format,
+			);
+			debug!("Saving to {:?}", path);
+			self.save_output((Some(image), None, None), File::create(&path)?);
+			return fs::write(
+				self.settings.split.dir.join("sheet.json"),
+				SpriteSheet::new(
+					PathBuf::from(path.file_name().unwrap_or_default()),
+					width,
+					height,
+					plays,
+					sheet_frames,
+				)
+				.get_json(),
+			);
+		}
+		let mut manifest_frames = Vec::new();
+		for (i, frame) in frames.iter().enumerate() {
 			let path = FileUtil::get_path_with_extension(
 				self.settings.split.dir.join(format!("frame_{}", i,)),
 				&self.settings.save.file.format,
 			);
 			debug!("Saving to {:?}\r", path);
 			io::stdout().flush().expect("Failed to flush stdout");
-			self.save_output((frames.get(i).cloned(), None), File::create(path)?);
+			self.save_output(
+				(Some(frame.clone()), None, None),
+				File::create(&path)?,
+			);
+			manifest_frames.push(ManifestFrame::new(
+				PathBuf::from(path.file_name().unwrap_or_default()),
+				frame.delay.unwrap_or_else(|| 1000 / fps.max(1)),
+				0,
+				0,
+			));
 		}
 		debug!("\n");
-		Ok(())
+		fs::write(
+			self.settings.split.dir.join("manifest.json"),
+			Manifest::new(width, height, plays, manifest_frames).get_json(),
+		)
 	}
 
 	/**
@@ -330,15 +697,15 @@ where
 	 * @param  output
 	 */
 	fn save_output<Output: Write>(&self, app_output: AppOutput, mut output: Output) {
-		let (image, frames) = app_output;
+		let (image, frames, plays) = app_output;
 		match self.settings.save.file.format {
 			FileFormat::Gif => {
 				debug!("{:?}", self.settings.anim);
-				self.save_gif(frames, output);
+				self.save_gif(frames, plays, output);
 			}
 			FileFormat::Apng => {
 				debug!("{:?}", self.settings.anim);
-				self.save_apng(frames, output);
+				self.save_apng(frames, plays, output);
 			}
 			FileFormat::Png => self.save_image(
 				image,
@@ -443,18 +810,54 @@ where
 	 * Save frames to a GIF file.
 	 *
 	 * @param  frames (Option)
+	 * @param  plays (Option)
+	 * @param  output
+	 */
+	fn save_gif<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		plays: Option<u32>,
+		mut output: Output,
+	) {
+		let frames = frames.expect("Failed to get the frames");
+		let settings = self.settings.anim.with_plays(plays);
+		match settings.max_size {
+			Some(max_size) => output
+				.write_all(
+					&SizeBudget::new(max_size, AnimFormat::Gif, &settings).fit(
+						frames,
+						|frames, settings, output| {
+							self.encode_gif(frames, settings, output)
+						},
+					),
+				)
+				.expect("Failed to write the frames"),
+			None => self.encode_gif(frames, &settings, output),
+		}
+	}
+
+	/**
+	 * Encode frames as GIF with the given settings.
+	 *
+	 * @param  frames
+	 * @param  settings
 	 * @param  output
 	 */
 	#[cfg(feature = "ski")]
-	fn save_gif<Output: Write>(self, frames: Option<Frames>, output: Output) {
-		let (images, fps) = frames.expect("Failed to get the frames");
+	fn encode_gif<Output: Write>(
+		self,
+		frames: Frames,
+		settings: &AnimSettings,
+		output: Output,
+	) {
+		let (images, fps) = frames;
 		let config = EncoderConfig::new(
 			fps,
 			images.first().expect("No frames found to save").geometry,
 			output,
-			&self.settings.anim,
+			settings,
 		);
-		if self.settings.anim.gifski.0 {
+		if settings.gifski.0 {
 			GifskiEncoder::new(config).save(images, self.settings.input_state)
 		} else {
 			GifEncoder::new(config).save(images, self.settings.input_state)
@@ -462,19 +865,25 @@ where
 	}
 
 	/**
-	 * Save frames to a GIF file.
+	 * Encode frames as GIF with the given settings.
 	 *
-	 * @param  frames (Option)
+	 * @param  frames
+	 * @param  settings
 	 * @param  output
 	 */
 	#[cfg(not(feature = "ski"))]
-	fn save_gif<Output: Write>(self, frames: Option<Frames>, output: Output) {
-		let (images, fps) = frames.expect("Failed to get the frames");
+	fn encode_gif<Output: Write>(
+		self,
+		frames: Frames,
+		settings: &AnimSettings,
+		output: Output,
+	) {
+		let (images, fps) = frames;
 		GifEncoder::new(EncoderConfig::new(
 			fps,
 			images.first().expect("No frames found to save").geometry,
 			output,
-			&self.settings.anim,
+			settings,
 		))
 		.save(images, self.settings.input_state)
 	}
@@ -483,14 +892,51 @@ where
 	 * Save frames to a APNG file.
 	 *
 	 * @param  frames (Option)
+	 * @param  plays (Option)
+	 * @param  output
+	 */
+	fn save_apng<Output: Write>(
+		self,
+		frames: Option<Frames>,
+		plays: Option<u32>,
+		mut output: Output,
+	) {
+		let frames = frames.expect("Failed to get the frames");
+		let settings = self.settings.anim.with_plays(plays);
+		match settings.max_size {
+			Some(max_size) => output
+				.write_all(
+					&SizeBudget::new(max_size, AnimFormat::Apng, &settings).fit(
+						frames,
+						|frames, settings, output| {
+							self.encode_apng(frames, settings, output)
+						},
+					),
+				)
+				.expect("Failed to write the frames"),
+			None => self.encode_apng(frames, &settings, output),
+		}
+	}
+
+	/**
+	 * Encode frames as APNG with the given settings.
+	 *
+	 * @param  frames
+	 * @param  settings
 	 * @param  output
 	 */
-	fn save_apng<Output: Write>(self, frames: Option<Frames>, output: Output) {
-		let images = frames.expect("Failed to get the frames").0;
+	fn encode_apng<Output: Write>(
+		self,
+		frames: Frames,
+		settings: &AnimSettings,
+		output: Output,
+	) {
+		let images = frames.0;
 		ApngEncoder::new(
 			images.len().try_into().unwrap_or_default(),
 			images.first().expect("No frames found to save").geometry,
-			&self.settings.anim,
+			settings,
+			&self.settings.png,
 		)
 		.save(images, self.settings.input_state, output);
 	}
@@ -503,7 +949,6 @@ mod tests {
 	use crate::window::test::TestWindow;
 	use clap::ArgMatches as Args;
 	use std::env;
-	use std::path::PathBuf;
 	#[test]
 	fn test_app_image() -> AppResult {
 		let args = Args::default();
@@ -526,7 +971,7 @@ mod tests {
 			settings.save.file.path = path.clone();
 			settings.analyze.file = path.clone();
 			let app = App::new(Some(window), &settings);
-			app.save_output((app.get_image(), None), File::create(&path)?);
+			app.save_output((app.get_image(), None, None), File::create(&path)?);
 			app.edit_image(&path);
 			app.analyze_image()?;
 			fs::remove_file(path)?;
@@ -546,7 +991,7 @@ mod tests {
 		let window = TestWindow::default();
 		let app = App::new(Some(window), &settings);
 		let images = app.get_frames().0;
-		app.save_gif(Some((images.clone(), 10)), File::create("test.gif")?);
+		app.save_gif(Some((images.clone(), 10)), None, File::create("test.gif")?);
 		app.edit_anim(File::open("test.gif")?, Path::new("test.gif"));
 		let dir = env::current_dir()?;
 		settings.split.dir = PathBuf::from(dir.to_str().unwrap_or_default());
@@ -554,8 +999,24 @@ mod tests {
 		settings.save.file.format = FileFormat::Png;
 		let app = App::new(Some(window), &settings);
 		app.split_anim(File::open("test.gif")?)?;
+		let manifest =
+			Manifest::parse(&fs::read_to_string("manifest.json")?, Path::new(""))
+				.expect("Failed to parse the manifest");
+		assert_eq!(images.len(), manifest.frames.len());
+		assert_eq!(Path::new("frame_0.png"), manifest.frames[0].file);
+		let images = images
+			.into_iter()
+			.map(|image| image.with_delay(Some(1500)))
+			.collect::<Vec<Image>>();
+		app.save_gif(Some((images.clone(), 10)), None, File::create("test.gif")?);
+		app.split_anim(File::open("test.gif")?)?;
+		let manifest =
+			Manifest::parse(&fs::read_to_string("manifest.json")?, Path::new(""))
+				.expect("Failed to parse the manifest");
+		assert_eq!(1500, manifest.frames[0].delay);
+		fs::remove_file("manifest.json")?;
 		fs::remove_file("test.gif")?;
-		app.save_apng(Some((images.clone(), 20)), File::create("test.apng")?);
+		app.save_apng(Some((images.clone(), 20)), None, File::create("test.apng")?);
 		fs::remove_file("test.apng")?;
 		for i in 0..images.len() {
 			let path = PathBuf::from(format!("frame_{}.png", i));
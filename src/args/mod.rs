@@ -34,6 +34,11 @@ pub struct Args<'a, 'b> {
 	capture: App<'a, 'b>,
 	edit: App<'a, 'b>,
 	analyze: App<'a, 'b>,
+	optimize: App<'a, 'b>,
+	pick: App<'a, 'b>,
+	measure: App<'a, 'b>,
+	montage: App<'a, 'b>,
+	daemon: App<'a, 'b>,
 	misc: App<'a, 'b>,
 }
 
@@ -79,6 +84,11 @@ where
 			capture: Self::get_record_args(true),
 			edit: Self::get_edit_args(),
 			analyze: Self::get_analyze_args(),
+			optimize: Self::get_optimize_args(),
+			pick: Self::get_pick_args(),
+			measure: Self::get_measure_args(),
+			montage: Self::get_montage_args(),
+			daemon: Self::get_daemon_args(),
 			misc: Self::get_misc_args(),
 		}
 	}
@@ -171,6 +181,14 @@ where
 				args.analyze
 					.subcommand(Self::get_save_args(FileFormat::Txt)),
 			)
+			.subcommand(
+				args.optimize
+					.subcommand(Self::get_save_args(FileFormat::Gif)),
+			)
+			.subcommand(args.pick)
+			.subcommand(Self::get_image_args(args.measure, true))
+			.subcommand(Self::get_image_args(args.montage, true))
+			.subcommand(args.daemon)
 			.subcommand(args.misc)
 	}
 
@@ -240,6 +258,12 @@ where
 					.help("Disable the action keys while recording")
 					.hidden(capture),
 			)
+			.arg(
+				Arg::with_name("wait")
+					.long("wait")
+					.help("Wait for a start command before recording")
+					.hidden(capture),
+			)
 			.arg(
 				Arg::with_name("keys")
 					.short("k")
@@ -255,6 +279,20 @@ where
 					.long("mouse")
 					.help("Select the window with mouse click"),
 			)
+			.arg(
+				Arg::with_name("drag")
+					.long("drag")
+					.help("Select an area by dragging the mouse")
+					.conflicts_with_all(&["size", "focus", "parent", "monitor"])
+					.hidden(cfg!(not(all(unix, not(target_os = "macos"))))),
+			)
+			.arg(
+				Arg::with_name("loupe")
+					.long("loupe")
+					.help("Show a magnifier while selecting the area")
+					.requires("drag")
+					.hidden(cfg!(not(all(unix, not(target_os = "macos"))))),
+			)
 			.arg(
 				Arg::with_name("border")
 					.short("b")
@@ -289,6 +327,47 @@ where
 					.empty_values(true)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("scale")
+					.long("scale")
+					.value_name("RATIO")
+					.default_value("1.0")
+					.help(if capture {
+						"Scale the image by the given ratio"
+					} else {
+						"Scale the frames by the given ratio"
+					})
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("max-width")
+					.long("max-width")
+					.value_name("WIDTH")
+					.help("Set the maximum width while scaling")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("max-height")
+					.long("max-height")
+					.value_name("HEIGHT")
+					.help("Set the maximum height while scaling")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("filter")
+					.long("filter")
+					.value_name("FILTER")
+					.possible_values(&[
+						"nearest",
+						"triangle",
+						"catmull-rom",
+						"gaussian",
+						"lanczos3",
+					])
+					.default_value("lanczos3")
+					.help("Set the sampling filter for scaling")
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("duration")
 					.short("d")
@@ -342,12 +421,82 @@ where
 			.arg(
 				Arg::with_name("monitor")
 					.long("monitor")
-					.value_name("NUM")
+					.value_name("MONITOR")
+					.help(if capture {
+						"Set the monitor(s) to capture as root window"
+					} else {
+						"Set the monitor(s) to record as root window"
+					})
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("background")
+					.long("background")
+					.value_name("HEX")
+					.default_value("000000")
+					.help("Set the color of the gaps between monitors")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("socket")
+					.long("socket")
+					.value_name("PATH")
+					.help("Set the socket for controlling the recording")
+					.takes_value(true)
+					.hidden(capture),
+			)
+			.arg(
+				Arg::with_name("headless")
+					.long("headless")
+					.value_name("WxH")
+					.help("Run the command inside a private X server")
+					.requires("command")
+					.takes_value(true)
+					.hidden(cfg!(not(all(unix, not(target_os = "macos"))))),
+			)
+			.arg(
+				Arg::with_name("server")
+					.long("server")
+					.value_name("SERVER")
+					.possible_values(&["xvfb", "xephyr"])
+					.default_value("xvfb")
+					.help("Set the X server to use in headless mode")
+					.takes_value(true)
+					.hidden(cfg!(not(all(unix, not(target_os = "macos"))))),
+			)
+			.arg(
+				Arg::with_name("vnc")
+					.long("vnc")
+					.value_name("HOST:PORT")
+					.help(if capture {
+						"Capture the framebuffer of a VNC server"
+					} else {
+						"Record the framebuffer of a VNC server"
+					})
+					.conflicts_with("headless")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("vnc-password")
+					.long("vnc-password")
+					.value_name("PASSWORD")
+					.help("Set the password for the VNC server")
+					.requires("vnc")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("pattern")
+					.long("pattern")
+					.value_name("PATTERN")
+					.possible_values(&[
+						"gradient", "boxes", "noise", "grid", "counter",
+					])
 					.help(if capture {
-						"Set the monitor to capture as root window"
+						"Capture a test pattern instead of a window"
 					} else {
-						"Set the monitor to record as root window"
+						"Record a test pattern instead of a window"
 					})
+					.conflicts_with_all(&["vnc", "headless"])
 					.takes_value(true),
 			)
 	}
@@ -396,6 +545,42 @@ where
 					.help("Set the number of repetitions")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("lossy")
+					.long("lossy")
+					.value_name("LEVEL")
+					.default_value("0")
+					.help("Set the lossy compression level (0-200)")
+					.takes_value(true)
+					.hidden(mode.has_format(AnimFormat::Apng)),
+			)
+			.arg(
+				Arg::with_name("compression")
+					.long("compression")
+					.value_name("COMPRESSION")
+					.possible_values(&["default", "fast", "best", "huffman", "rle"])
+					.default_value("best")
+					.help("Set the compression level (APNG)")
+					.takes_value(true)
+					.hidden(mode.has_format(AnimFormat::Gif)),
+			)
+			.arg(
+				Arg::with_name("filter")
+					.long("filter")
+					.value_name("FILTER")
+					.possible_values(&["none", "sub", "up", "avg", "paeth"])
+					.default_value("sub")
+					.help("Set the filter algorithm (APNG)")
+					.takes_value(true)
+					.hidden(mode.has_format(AnimFormat::Gif)),
+			)
+			.arg(
+				Arg::with_name("max-size")
+					.long("max-size")
+					.value_name("SIZE")
+					.help("Set the maximum file size (e.g. 5MB)")
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("gifski")
 					.long("gifski")
@@ -420,8 +605,15 @@ where
 					.long("speed")
 					.value_name("SPEED")
 					.default_value("1.0")
-					.help("Set the animation speed")
-					.hidden(!mode.is_edit())
+					.help("Set the animation speed (or speed segments)")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("retime")
+					.long("retime")
+					.value_name("MODE")
+					.possible_values(&["blend", "motion"])
+					.help("Synthesize the frames while changing the speed")
 					.takes_value(true),
 			)
 			.arg(
@@ -442,6 +634,20 @@ where
 					.hidden(!mode.is_edit())
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("target-fps")
+					.long("target-fps")
+					.value_name("FPS")
+					.help("Resample the animation to the given FPS")
+					.hidden(!mode.is_edit())
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("blend")
+					.long("blend")
+					.help("Blend the frames while lowering the FPS")
+					.hidden(!mode.is_edit()),
+			)
 			.arg(
 				Arg::with_name("frames")
 					.value_name("FRAMES")
@@ -449,6 +655,9 @@ where
 					.min_values(1)
 					.hidden(mode != AnimMode::Make)
 					.default_value_if("dir", None, "-")
+					.default_value_if("manifest", None, "-")
+					.default_value_if("sheet", None, "-")
+					.default_value_if("atlas", None, "-")
 					.required(mode == AnimMode::Make)
 					.empty_values(false)
 					.takes_value(true),
@@ -464,12 +673,44 @@ where
 				Arg::with_name("dir")
 					.short("d")
 					.long("dir")
-					.conflicts_with("frames")
 					.value_name("DIRECTORY")
 					.help("Set the directory to read frames")
 					.hidden(mode != AnimMode::Make)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("manifest")
+					.long("manifest")
+					.value_name("FILE")
+					.help("Set the manifest file to read frames and delays")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("sheet")
+					.long("sheet")
+					.value_name("FILE")
+					.help("Set the sprite sheet to read frames")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("grid")
+					.long("grid")
+					.value_name("COLSxROWS")
+					.help("Set the grid dimensions of the sprite sheet")
+					.requires("sheet")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("atlas")
+					.long("atlas")
+					.value_name("FILE")
+					.help("Set the atlas file to read frames and delays")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
 			.arg(
 				Arg::with_name("format")
 					.long("format")
@@ -480,6 +721,32 @@ where
 					.hidden(mode != AnimMode::Make)
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("hold")
+					.long("hold")
+					.value_name("S")
+					.help("Set the duration to show each frame (e.g. 2 or 3,1,1)")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("transition")
+					.long("transition")
+					.value_name("TRANSITION")
+					.help("Set the transition between the frames")
+					.possible_values(&["crossfade", "slide", "wipe"])
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("transition-duration")
+					.long("transition-duration")
+					.value_name("S")
+					.help("Set the duration of the transitions")
+					.default_value("0.5")
+					.hidden(mode != AnimMode::Make)
+					.takes_value(true),
+			)
 	}
 
 	/**
@@ -626,6 +893,22 @@ where
 					.help("Set the output directory")
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("sheet")
+					.long("sheet")
+					.value_name("LAYOUT")
+					.help("Save the frames as a sprite sheet with atlas")
+					.possible_values(&["grid", "packed"])
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("columns")
+					.long("columns")
+					.value_name("N")
+					.help("Set the number of columns in the grid")
+					.requires("sheet")
+					.takes_value(true),
+			)
 	}
 
 	/**
@@ -661,6 +944,198 @@ where
 			)
 	}
 
+	/**
+	 * Get the animation optimization arguments.
+	 *
+	 * @return App
+	 */
+	fn get_optimize_args() -> App<'a, 'b> {
+		SubCommand::with_name("optimize")
+			.about("Optimize an animation")
+			.help_message("Print help information")
+			.alias("shrink")
+			.arg(
+				Arg::with_name("file")
+					.value_name("FILE")
+					.help("Set the animation file")
+					.required(true),
+			)
+			.arg(
+				Arg::with_name("colors")
+					.long("colors")
+					.value_name("COLORS")
+					.default_value("256")
+					.help("Set the maximum number of colors (2-256)")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("fuzz")
+					.long("fuzz")
+					.value_name("FUZZ")
+					.default_value("0")
+					.help("Set the color tolerance for unchanged pixels (%)")
+					.takes_value(true),
+			)
+	}
+
+	/**
+	 * Get the color picker arguments.
+	 *
+	 * @return App
+	 */
+	fn get_pick_args() -> App<'a, 'b> {
+		SubCommand::with_name("pick")
+			.about("Pick colors from the screen")
+			.help_message("Print help information")
+			.alias("color")
+			.settings(if cfg!(all(unix, not(target_os = "macos"))) {
+				&[]
+			} else {
+				&[AppSettings::Hidden]
+			})
+			.arg(
+				Arg::with_name("count")
+					.short("n")
+					.long("count")
+					.value_name("N")
+					.default_value("1")
+					.help("Set the number of colors to pick")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("json")
+					.long("json")
+					.help("Print the colors in JSON format"),
+			)
+	}
+
+	/**
+	 * Get the measurement arguments.
+	 *
+	 * @return App
+	 */
+	fn get_measure_args() -> App<'a, 'b> {
+		SubCommand::with_name("measure")
+			.about("Measure distances and areas on the screen")
+			.help_message("Print help information")
+			.alias("ruler")
+			.settings(if cfg!(all(unix, not(target_os = "macos"))) {
+				&[]
+			} else {
+				&[AppSettings::Hidden]
+			})
+	}
+
+	/**
+	 * Get the montage arguments.
+	 *
+	 * @return App
+	 */
+	fn get_montage_args() -> App<'a, 'b> {
+		SubCommand::with_name("montage")
+			.about("Compose images or animation frames into a grid")
+			.help_message("Print help information")
+			.alias("grid")
+			.arg(
+				Arg::with_name("files")
+					.value_name("FILES")
+					.help("Set the image or animation files")
+					.min_values(1)
+					.required(true),
+			)
+			.arg(
+				Arg::with_name("frames")
+					.long("frames")
+					.value_name("N")
+					.default_value("0")
+					.help("Set the number of frames to select from animations")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("columns")
+					.short("c")
+					.long("columns")
+					.value_name("N")
+					.default_value("0")
+					.help("Set the number of columns in the grid")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("spacing")
+					.short("s")
+					.long("spacing")
+					.value_name("PX")
+					.default_value("4")
+					.help("Set the spacing between the tiles")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("background")
+					.short("b")
+					.long("background")
+					.value_name("HEX")
+					.default_value("FFFFFF")
+					.help("Set the background color")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("label")
+					.short("l")
+					.long("label")
+					.value_name("LABEL")
+					.possible_values(&["name", "time"])
+					.help("Label the tiles with the file name or timestamp")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("tile")
+					.short("t")
+					.long("tile")
+					.value_name("WxH")
+					.help("Set the size of the tiles")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("filter")
+					.long("filter")
+					.value_name("FILTER")
+					.possible_values(&[
+						"nearest",
+						"triangle",
+						"catmull-rom",
+						"gaussian",
+						"lanczos3",
+					])
+					.default_value("lanczos3")
+					.help("Set the sampling filter for scaling")
+					.takes_value(true),
+			)
+	}
+
+	/**
+	 * Get the daemon arguments.
+	 *
+	 * @return App
+	 */
+	fn get_daemon_args() -> App<'a, 'b> {
+		SubCommand::with_name("daemon")
+			.about("Listen for hotkeys to capture/record")
+			.help_message("Print help information")
+			.settings(if cfg!(all(unix, not(target_os = "macos"))) {
+				&[]
+			} else {
+				&[AppSettings::Hidden]
+			})
+			.arg(
+				Arg::with_name("hotkeys")
+					.short("k")
+					.long("hotkeys")
+					.value_name("HOTKEYS")
+					.help("Set the hotkeys and their profiles")
+					.takes_value(true),
+			)
+	}
+
 	/**
 	 * Add image related subcommands to the given arguments.
 	 *
@@ -866,6 +1341,13 @@ where
 					])
 					.takes_value(true),
 			)
+			.arg(
+				Arg::with_name("list-monitors")
+					.short("m")
+					.long("list-monitors")
+					.help("List the available monitors")
+					.hidden(cfg!(not(all(unix, not(target_os = "macos"))))),
+			)
 	}
 }
 
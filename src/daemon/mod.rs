@@ -0,0 +1,130 @@
+pub mod settings;
+
+use std::env;
+use std::io::Error;
+use std::process::{Child, Command};
+use std::str::FromStr;
+
+/* Modifier keys of a hotkey */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Modifier {
+	Shift,
+	Control,
+	Alt,
+	Super,
+}
+
+/* Implementation for parsing Modifier from a string */
+impl FromStr for Modifier {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"shift" => Ok(Self::Shift),
+			"control" | "ctrl" => Ok(Self::Control),
+			"alt" | "mod1" => Ok(Self::Alt),
+			"super" | "mod4" => Ok(Self::Super),
+			_ => Err(format!("Invalid modifier ({})", s)),
+		}
+	}
+}
+
+/* Global hotkey that is mapped to a profile */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hotkey {
+	pub modifiers: Vec<Modifier>,
+	pub key: String,
+	pub profile: String,
+}
+
+/* Implementation for parsing Hotkey from a string (MODIFIERS-KEY:PROFILE) */
+impl FromStr for Hotkey {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let values = s.trim().splitn(2, ':').collect::<Vec<&str>>();
+		let (keys, profile) = match values.as_slice() {
+			[keys, profile] if !keys.is_empty() && !profile.is_empty() => {
+				(keys, profile)
+			}
+			_ => return Err(format!("Invalid hotkey ({})", s)),
+		};
+		let mut keys = keys.split('-').collect::<Vec<&str>>();
+		let key = keys.pop().unwrap_or_default();
+		Ok(Self {
+			modifiers: keys
+				.into_iter()
+				.map(Modifier::from_str)
+				.collect::<Result<Vec<Modifier>, String>>()?,
+			key: key.to_string(),
+			profile: profile.to_string(),
+		})
+	}
+}
+
+/* Capture/record profile that runs with the given arguments */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+	pub name: String,
+	pub args: Vec<String>,
+}
+
+impl Profile {
+	/**
+	 * Create a new Profile object.
+	 *
+	 * @param  name
+	 * @param  args
+	 * @return Profile
+	 */
+	pub fn new(name: &str, args: &str) -> Self {
+		Self {
+			name: name.to_string(),
+			args: args.split_whitespace().map(String::from).collect(),
+		}
+	}
+
+	/**
+	 * Run the profile as a new process.
+	 *
+	 * @param  global_args
+	 * @return Child (Result)
+	 */
+	pub fn run(&self, global_args: &[String]) -> Result<Child, Error> {
+		info!(
+			"Running the profile: {} ({})",
+			self.name,
+			self.args.join(" ")
+		);
+		Command::new(env::current_exe()?)
+			.args(global_args)
+			.args(&self.args)
+			.spawn()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_hotkey() {
+		assert_eq!(
+			Ok(Hotkey {
+				modifiers: vec![Modifier::Control, Modifier::Shift],
+				key: String::from("Print"),
+				profile: String::from("screenshot"),
+			}),
+			Hotkey::from_str("Ctrl-Shift-Print:screenshot")
+		);
+		assert_eq!(
+			Ok(Vec::new()),
+			Hotkey::from_str("F9:record").map(|hotkey| hotkey.modifiers)
+		);
+		assert!(Hotkey::from_str("Print").is_err());
+		assert!(Hotkey::from_str("Print:").is_err());
+		assert!(Hotkey::from_str("Hyper-Print:test").is_err());
+		assert_eq!(
+			vec!["capture", "--root", "png"],
+			Profile::new("test", " capture --root  png ").args
+		);
+	}
+}
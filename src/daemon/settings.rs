@@ -0,0 +1,145 @@
+use crate::args::matches::ArgMatches;
+use crate::args::parser::ArgParser;
+use crate::daemon::{Hotkey, Profile};
+use std::str::FromStr;
+
+/* Daemon settings */
+#[derive(Debug, Default)]
+pub struct DaemonSettings {
+	pub hotkeys: Vec<Hotkey>,
+	pub profiles: Vec<Profile>,
+	pub args: Vec<String>,
+}
+
+impl DaemonSettings {
+	/**
+	 * Create a new DaemonSettings object.
+	 *
+	 * @param  hotkeys
+	 * @param  profiles
+	 * @param  args
+	 * @return DaemonSettings
+	 */
+	pub fn new(
+		hotkeys: Vec<Hotkey>,
+		profiles: Vec<Profile>,
+		args: Vec<String>,
+	) -> Self {
+		Self {
+			hotkeys,
+			profiles,
+			args,
+		}
+	}
+
+	/**
+	 * Create a new DaemonSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return DaemonSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		let mut args = Vec::new();
+		if let Some(config) = matches.args.value_of("config") {
+			args.push(String::from("--config"));
+			args.push(config.to_string());
+		}
+		for _ in 0..matches.args.occurrences_of("verbose") {
+			args.push(String::from("-v"));
+		}
+		Self::from_parser(ArgParser::from_subcommand(matches, "daemon"), args)
+	}
+
+	/**
+	 * Create a DaemonSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @param  args
+	 * @return DaemonSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>, args: Vec<String>) -> Self {
+		match parser.args {
+			Some(matches) => {
+				let profiles = match matches
+					.config
+					.as_ref()
+					.and_then(|config| config.section(Some("profiles")))
+				{
+					Some(section) => section
+						.iter()
+						.map(|(name, args)| Profile::new(name, args))
+						.collect(),
+					None => Vec::new(),
+				};
+				let hotkeys = matches
+					.value_of("hotkeys")
+					.unwrap_or_default()
+					.split(',')
+					.filter(|hotkey| !hotkey.trim().is_empty())
+					.map(|hotkey| {
+						Hotkey::from_str(hotkey).unwrap_or_else(|e| panic!("{}", e))
+					})
+					.collect::<Vec<Hotkey>>();
+				for hotkey in &hotkeys {
+					if !profiles.iter().any(|p| p.name == hotkey.profile) {
+						panic!("Profile not found ({})", hotkey.profile);
+					}
+				}
+				Self::new(hotkeys, profiles, args)
+			}
+			None => Self::default(),
+		}
+	}
+
+	/**
+	 * Get the profile of the given hotkey.
+	 *
+	 * @param  hotkey
+	 * @return Profile (Option)
+	 */
+	pub fn get_profile(&self, hotkey: &Hotkey) -> Option<&Profile> {
+		self.profiles
+			.iter()
+			.find(|profile| profile.name == hotkey.profile)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, Arg, SubCommand};
+	use ini::Ini;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_daemon_settings() {
+		let args =
+			App::new("test")
+				.subcommand(SubCommand::with_name("daemon").arg(
+					Arg::with_name("hotkeys").long("hotkeys").takes_value(true),
+				))
+				.get_matches_from(vec![
+					"test",
+					"daemon",
+					"--hotkeys",
+					"Print:screenshot, Shift-F9:record",
+				]);
+		let mut config = Ini::new();
+		config
+			.with_section(Some("profiles"))
+			.set("screenshot", "capture --root png save -t")
+			.set("record", "record --select gif");
+		let matches = ArgMatches {
+			args: &args,
+			config: Some(config),
+			section: "general",
+		};
+		let daemon_settings = DaemonSettings::from_args(&matches);
+		assert_eq!(2, daemon_settings.hotkeys.len());
+		assert_eq!(
+			Some(&Profile::new("record", "record --select gif")),
+			daemon_settings.get_profile(&daemon_settings.hotkeys[1])
+		);
+		assert!(daemon_settings.args.is_empty());
+		assert!(DaemonSettings::default().hotkeys.is_empty());
+	}
+}
@@ -64,6 +64,22 @@ impl ImageSettings {
 			filter,
 		}
 	}
+
+	/**
+	 * Parse the sampling filter from the given value.
+	 *
+	 * @param  filter (Option)
+	 * @return FilterType
+	 */
+	pub fn parse_filter(filter: Option<&str>) -> FilterType {
+		match filter {
+			Some("nearest") => FilterType::Nearest,
+			Some("triangle") => FilterType::Triangle,
+			Some("catmull-rom") => FilterType::CatmullRom,
+			Some("gaussian") => FilterType::Gaussian,
+			_ => FilterType::Lanczos3,
+		}
+	}
 }
 
 /* Image color settings */
@@ -201,13 +217,7 @@ impl EditSettings {
 					},
 					parser.parse("rotate", ImageSettings::default().rotate),
 					parser.parse("blur", ImageSettings::default().blur),
-					match matches.value_of("filter") {
-						Some("nearest") => FilterType::Nearest,
-						Some("triangle") => FilterType::Triangle,
-						Some("catmull-rom") => FilterType::CatmullRom,
-						Some("gaussian") => FilterType::Gaussian,
-						_ => FilterType::Lanczos3,
-					},
+					ImageSettings::parse_filter(matches.value_of("filter")),
 				),
 				ColorSettings::new(
 					matches.is_present("grayscale"),
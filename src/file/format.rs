@@ -67,6 +67,8 @@ impl FileFormat {
 			"split"
 		} else if args.is_present("analyze") {
 			"analyze"
+		} else if args.is_present("montage") {
+			"montage"
 		} else {
 			"capture"
 		}) {
@@ -79,6 +81,8 @@ impl FileFormat {
 					}
 				} else if matches.is_present("gif") {
 					Self::Gif
+				} else if matches.is_present("apng") {
+					Self::Apng
 				} else if matches.is_present("ff") {
 					Self::Ff
 				} else if matches.is_present("tiff") {
@@ -1,3 +1,4 @@
+use crate::anim::AnimFormat;
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
 use crate::edit::settings::EditSettings;
@@ -5,6 +6,7 @@ use crate::file::format::FileFormat;
 use crate::file::info::FileInfo;
 use crate::file::File;
 use crate::image::settings::PnmSettings;
+use crate::optimize::settings::OptimizeSettings;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -42,6 +44,11 @@ impl SaveSettings {
 				args.value_of("format").map_or(FileFormat::Gif, |f| {
 					FileFormat::from_str(f).unwrap_or(FileFormat::Gif)
 				})
+			} else if matches.is_present("optimize") {
+				match OptimizeSettings::from_args(matches).get_format() {
+					AnimFormat::Gif => FileFormat::Gif,
+					AnimFormat::Apng => FileFormat::Apng,
+				}
 			} else {
 				FileFormat::from_args(matches, Some(pnm.subtype))
 			};
@@ -50,21 +50,22 @@ pub trait Encoder<'a, Output: Write> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::anim::AnimFormat;
 	#[cfg(feature = "ski")]
 	use crate::gif::ski::GifskiEncoder;
 	use crate::gif::GifEncoder;
-	use image::Bgra;
+	use crate::window::pattern::{Pattern, PatternWindow};
+	use image::gif::GifDecoder;
+	use image::AnimationDecoder;
+	use pretty_assertions::assert_eq;
 	const GIF_HEADER: &[u8] = &[0x47, 0x49, 0x46, 0x38, 0x39, 0x61];
 	fn get_config<'a, Output: Write>(
 		output: Output,
 		settings: &'a AnimSettings,
 	) -> (EncoderConfig<'a, Output>, Vec<Image>) {
-		let geometry = Geometry::new(0, 0, 1, 2);
-		let data = vec![Bgra::from([0, 0, 0, 0]), Bgra::from([255, 255, 255, 0])];
-		let images = vec![
-			Image::new(data.clone(), false, geometry),
-			Image::new(data.into_iter().rev().collect(), false, geometry),
-		];
+		let geometry = Geometry::new(0, 0, 32, 24);
+		let window = PatternWindow::new(Pattern::Boxes, geometry);
+		let images = (0..3).map(|frame| window.get_frame(frame)).collect();
 		(EncoderConfig::new(10, geometry, output, settings), images)
 	}
 	#[test]
@@ -73,9 +74,62 @@ mod tests {
 		let settings = AnimSettings::default();
 		let (config, images) = get_config(&mut output, &settings);
 		GifEncoder::new(config).save(images.clone(), None);
+		assert_eq!(
+			images.len(),
+			GifDecoder::new(output.as_slice())
+				.unwrap()
+				.into_frames()
+				.collect_frames()
+				.unwrap()
+				.len()
+		);
+		assert_eq!(0, AnimFormat::Gif.get_plays(&output));
 		output.truncate(6);
 		assert_eq!(GIF_HEADER, output);
 		output.clear();
+		for repeat in 0..3 {
+			let settings = AnimSettings {
+				repeat,
+				..AnimSettings::default()
+			};
+			let (config, images) = get_config(&mut output, &settings);
+			GifEncoder::new(config).save(images, None);
+			assert_eq!(repeat as u32 + 1, AnimFormat::Gif.get_plays(&output));
+			output.clear();
+		}
+	}
+	#[test]
+	fn test_gif_lossy() {
+		let geometry = Geometry::new(0, 0, 64, 48);
+		let window = PatternWindow::new(Pattern::Gradient, geometry);
+		let images = (0..3)
+			.map(|frame| window.get_frame(frame))
+			.collect::<Vec<_>>();
+		let mut sizes = Vec::new();
+		for lossy in &[0, 20, 40, 80, 120, 200] {
+			let mut output = Vec::new();
+			let settings = AnimSettings {
+				lossy: *lossy,
+				..AnimSettings::default()
+			};
+			GifEncoder::new(EncoderConfig::new(
+				10,
+				geometry,
+				&mut output,
+				&settings,
+			))
+			.save(images.clone(), None);
+			let frames = GifDecoder::new(output.as_slice())
+				.unwrap()
+				.into_frames()
+				.collect_frames()
+				.unwrap();
+			assert_eq!(images.len(), frames.len());
+			assert_eq!((64, 48), frames[0].buffer().dimensions());
+			sizes.push(output.len());
+		}
+		assert!(sizes.windows(2).all(|size| size[1] <= size[0]));
+		assert!(sizes[sizes.len() - 1] < sizes[0]);
 	}
 	#[cfg(feature = "ski")]
 	#[test]
@@ -83,8 +137,25 @@ mod tests {
 		let mut output = Vec::new();
 		let settings = AnimSettings::default();
 		let (config, images) = get_config(&mut output, &settings);
-		GifskiEncoder::new(config).save(images, None);
+		GifskiEncoder::new(config).save(images.clone(), None);
+		assert_eq!(
+			images.len(),
+			GifDecoder::new(output.as_slice())
+				.unwrap()
+				.into_frames()
+				.collect_frames()
+				.unwrap()
+				.len()
+		);
 		output.truncate(6);
 		assert_eq!(GIF_HEADER, output);
+		output.clear();
+		let settings = AnimSettings {
+			repeat: 0,
+			..AnimSettings::default()
+		};
+		let (config, images) = get_config(&mut output, &settings);
+		GifskiEncoder::new(config).save(images, None);
+		assert_eq!(1, AnimFormat::Gif.get_plays(&output));
 	}
 }
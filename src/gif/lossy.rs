@@ -0,0 +1,146 @@
+/* Maximum number of codes in the LZW dictionary of GIF */
+const MAX_CODES: usize = 4096;
+
+/* Lossy LZW compressor that reuses the dictionary entries within a tolerance */
+#[derive(Debug)]
+pub struct LossyLzw<'a> {
+	palette: &'a [u8],
+	transparent: Option<u8>,
+	tolerance: u32,
+}
+
+impl<'a> LossyLzw<'a> {
+	/**
+	 * Create a new LossyLzw object.
+	 *
+	 * @param  palette
+	 * @param  transparent (Option)
+	 * @param  level
+	 * @return LossyLzw
+	 */
+	pub fn new(palette: &'a [u8], transparent: Option<u8>, level: u8) -> Self {
+		Self {
+			palette,
+			transparent,
+			tolerance: u32::from(level).pow(2) / 4,
+		}
+	}
+
+	/**
+	 * Get the squared distance between the colors of the given indices.
+	 *
+	 * @param  index1
+	 * @param  index2
+	 * @return u32
+	 */
+	fn get_distance(&self, index1: u8, index2: u8) -> u32 {
+		let get_color = |index: u8| {
+			let offset = usize::from(index) * 3;
+			self.palette.get(offset..offset + 3)
+		};
+		let (color1, color2) = (get_color(index1), get_color(index2));
+		match (color1, color2) {
+			(Some(color1), Some(color2)) => color1
+				.iter()
+				.zip(color2)
+				.map(|(c1, c2)| (i32::from(*c1) - i32::from(*c2)).pow(2) as u32)
+				.sum(),
+			_ => u32::MAX,
+		}
+	}
+
+	/**
+	 * Find the dictionary entry that matches the given index.
+	 *
+	 * @param  entries
+	 * @param  index
+	 * @return (u8, usize) (Option)
+	 */
+	fn find_entry(&self, entries: &[(u8, usize)], index: u8) -> Option<(u8, usize)> {
+		if let Some(entry) = entries.iter().find(|(i, _)| *i == index) {
+			return Some(*entry);
+		} else if self.transparent == Some(index) {
+			return None;
+		}
+		entries
+			.iter()
+			.filter(|(i, _)| self.transparent != Some(*i))
+			.map(|entry| (self.get_distance(entry.0, index), entry))
+			.filter(|(distance, _)| *distance <= self.tolerance)
+			.min_by_key(|(distance, _)| *distance)
+			.map(|(_, entry)| *entry)
+	}
+
+	/**
+	 * Process the indexed pixels and return the lossy version of them.
+	 *
+	 * The pixels are replaced with the ones of the longest dictionary
+	 * string that is close enough, so the LZW encoder outputs fewer codes.
+	 *
+	 * @param  pixels
+	 * @return Vector of u8
+	 */
+	pub fn process(&self, pixels: &[u8]) -> Vec<u8> {
+		let mut output = Vec::with_capacity(pixels.len());
+		let first = match pixels.first() {
+			Some(pixel) => *pixel,
+			None => return output,
+		};
+		let colors = (self.palette.len() / 3)
+			.max(usize::from(*pixels.iter().max().unwrap_or(&first)) + 1);
+		let min_code_size = match colors.next_power_of_two().trailing_zeros() {
+			0 | 1 => 2,
+			n => n,
+		};
+		let first_code = (1 << min_code_size) + 2;
+		let mut dictionary = vec![Vec::<(u8, usize)>::new(); MAX_CODES];
+		let mut next_code = first_code;
+		let mut code = usize::from(first);
+		output.push(first);
+		for pixel in pixels.iter().skip(1) {
+			match self.find_entry(&dictionary[code], *pixel) {
+				Some((index, child)) => {
+					output.push(index);
+					code = child;
+				}
+				None => {
+					if next_code < MAX_CODES {
+						dictionary[code].push((*pixel, next_code));
+						next_code += 1;
+					} else {
+						dictionary.iter_mut().for_each(|entries| entries.clear());
+						next_code = first_code;
+					}
+					output.push(*pixel);
+					code = usize::from(*pixel);
+				}
+			}
+		}
+		output
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_lossy_lzw() {
+		let palette = [0, 0, 0, 10, 10, 10, 255, 255, 255, 12, 12, 12];
+		let pixels = [0, 1, 0, 1, 0, 1, 0, 1, 2, 3];
+		assert_eq!(
+			pixels.to_vec(),
+			LossyLzw::new(&palette, None, 0).process(&pixels)
+		);
+		assert_eq!(
+			vec![0, 1, 0, 1, 0, 1, 0, 1, 2, 3],
+			LossyLzw::new(&palette, Some(1), 100).process(&pixels)
+		);
+		let pixels = [0, 1, 0, 1, 0, 3, 0, 3, 0, 1, 2];
+		assert_eq!(
+			vec![0, 1, 0, 1, 0, 1, 0, 3, 0, 1, 2],
+			LossyLzw::new(&palette, None, 20).process(&pixels)
+		);
+		assert!(LossyLzw::new(&palette, None, 200).process(&[]).is_empty());
+	}
+}
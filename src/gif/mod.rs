@@ -1,13 +1,17 @@
 pub mod encoder;
+pub mod lossy;
 #[cfg(feature = "ski")]
 pub mod ski;
 
 use crate::anim::settings::AnimSettings;
 use crate::gif::encoder::{Encoder, EncoderConfig};
+use crate::gif::lossy::LossyLzw;
 use crate::image::Image;
 use crate::util::state::InputState;
-use gif::{Encoder as BaseEncoder, Frame, Repeat};
+use color_quant::NeuQuant;
+use gif::{DisposalMethod, Encoder as BaseEncoder, Frame, Repeat};
 use image::ExtendedColorType;
+use std::borrow::Cow;
 use std::convert::TryInto;
 use std::io::{self, Write};
 
@@ -33,12 +37,14 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifEncoder<'a, Output> {
 			&[],
 		)
 		.expect("Failed to create a GIF encoder");
-		encoder
-			.set_repeat(match config.settings.repeat {
-				n if n >= 0 => Repeat::Finite(n.try_into().unwrap_or_default()),
-				_ => Repeat::Infinite,
-			})
-			.expect("Failed to set repeat count");
+		if config.settings.repeat != 0 {
+			encoder
+				.set_repeat(match config.settings.repeat {
+					n if n > 0 => Repeat::Finite(n.try_into().unwrap_or_default()),
+					_ => Repeat::Infinite,
+				})
+				.expect("Failed to set repeat count");
+		}
 		Self {
 			fps: config.fps,
 			encoder,
@@ -59,6 +65,7 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifEncoder<'a, Output> {
 				(1., 100.),
 				(0., 29.),
 			) as i32;
+		let mut time = 0;
 		for (i, image) in images.iter().enumerate() {
 			let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
 			info!("Saving... ({:.1}%)\r", percentage);
@@ -76,13 +83,28 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifEncoder<'a, Output> {
 					panic!("Failed to write the frames")
 				}
 			}
-			let mut frame = Frame::from_rgba_speed(
-				image.geometry.width.try_into().unwrap_or_default(),
-				image.geometry.height.try_into().unwrap_or_default(),
-				&mut image.get_data(ExtendedColorType::Rgba8),
-				speed,
-			);
-			frame.delay = (1e2 / self.fps as f32) as u16;
+			let mut frame = self.get_frame(image, speed);
+			if self.settings.lossy > 0 {
+				frame.buffer = Cow::Owned(
+					LossyLzw::new(
+						frame.palette.as_deref().unwrap_or_default(),
+						frame.transparent,
+						self.settings.lossy,
+					)
+					.process(&frame.buffer),
+				);
+			}
+			frame.delay = match image.delay {
+				Some(delay) => {
+					let elapsed = (time as f32 / 10.).round();
+					time += delay;
+					((time as f32 / 10.).round() - elapsed) as u16
+				}
+				None => (1e2 / self.fps as f32) as u16,
+			};
+			if frame.transparent.is_some() {
+				frame.dispose = DisposalMethod::Background;
+			}
 			self.encoder.write_frame(&frame).unwrap_or_else(|_| {
 				panic!("Failed to write frame: {}/{}", i + 1, images.len())
 			});
@@ -90,3 +112,42 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifEncoder<'a, Output> {
 		info!("\n");
 	}
 }
+
+impl<'a, Output: Write> GifEncoder<'a, Output> {
+	/**
+	 * Get a GIF frame from the image using the palette size in settings.
+	 *
+	 * @param  image
+	 * @param  speed
+	 * @return Frame
+	 */
+	fn get_frame(&self, image: &Image, speed: i32) -> Frame<'static> {
+		let (width, height) = (
+			image.geometry.width.try_into().unwrap_or_default(),
+			image.geometry.height.try_into().unwrap_or_default(),
+		);
+		let mut data = image.get_data(ExtendedColorType::Rgba8);
+		if self.settings.colors >= 256 {
+			return Frame::from_rgba_speed(width, height, &mut data, speed);
+		}
+		let mut transparent = None;
+		for pixel in data.chunks_mut(4) {
+			if pixel[3] != 0 {
+				pixel[3] = 0xFF;
+			} else {
+				transparent = Some([pixel[0], pixel[1], pixel[2], pixel[3]]);
+			}
+		}
+		let quant = NeuQuant::new(speed, self.settings.colors.into(), &data);
+		Frame::from_palette_pixels(
+			width,
+			height,
+			&data
+				.chunks(4)
+				.map(|pixel| quant.index_of(pixel) as u8)
+				.collect::<Vec<u8>>(),
+			&quant.color_map_rgb(),
+			transparent.map(|pixel| quant.index_of(&pixel) as u8),
+		)
+	}
+}
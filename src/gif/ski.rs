@@ -9,6 +9,7 @@ use std::thread;
 /* GIF encoder and settings */
 pub struct GifskiEncoder<Output: Write> {
 	fps: u32,
+	play_once: bool,
 	collector: Collector,
 	writer: Writer,
 	output: Output,
@@ -35,6 +36,7 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifskiEncoder<Output> {
 		.expect("Failed to initialize the gifski encoder");
 		Self {
 			fps: config.fps,
+			play_once: config.settings.repeat == 0,
 			collector,
 			writer,
 			output: config.output,
@@ -51,6 +53,7 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifskiEncoder<Output> {
 		let fps = self.fps;
 		let mut collector = self.collector;
 		let collector_thread = thread::spawn(move || {
+			let mut timestamp = 0.;
 			for (i, image) in images.iter().enumerate() {
 				let percentage = ((i + 1) as f64 / images.len() as f64) * 100.;
 				info!("Saving... ({:.1}%)\r", percentage);
@@ -69,16 +72,47 @@ impl<'a, Output: Write> Encoder<'a, Output> for GifskiEncoder<Output> {
 					}
 				}
 				collector
-					.add_frame_rgba(i, image.get_img_vec(), i as f64 / fps as f64)
+					.add_frame_rgba(i, image.get_img_vec(), timestamp)
 					.expect("Failed to collect a frame");
+				timestamp += image
+					.delay
+					.map_or(1. / fps as f64, |delay| delay as f64 / 1e3);
 			}
 			info!("\n");
 		});
-		self.writer
-			.write(self.output, &mut gifski::progress::NoProgress {})
-			.expect("Failed to write the frames");
+		if self.play_once {
+			let mut data = Vec::new();
+			self.writer
+				.write(&mut data, &mut gifski::progress::NoProgress {})
+				.expect("Failed to write the frames");
+			let mut output = self.output;
+			output
+				.write_all(&remove_loop_extension(data))
+				.expect("Failed to write the frames");
+		} else {
+			self.writer
+				.write(self.output, &mut gifski::progress::NoProgress {})
+				.expect("Failed to write the frames");
+		}
 		collector_thread
 			.join()
 			.expect("Failed to collect the frames");
 	}
 }
+
+/**
+ * Remove the loop extension (NETSCAPE2.0) from the GIF data.
+ *
+ * @param  data
+ * @return Vector of u8
+ */
+fn remove_loop_extension(mut data: Vec<u8>) -> Vec<u8> {
+	if let Some(position) = data
+		.windows(14)
+		.position(|window| window == b"\x21\xFF\x0BNETSCAPE2.0")
+	{
+		let end = (position + 19).min(data.len());
+		data.drain(position..end);
+	}
+	data
+}
@@ -64,6 +64,55 @@ impl Geometry {
 			.collect()
 	}
 
+	/**
+	 * Create a Geometry object from two corner points.
+	 *
+	 * @param  start
+	 * @param  end
+	 * @return Geometry
+	 */
+	pub fn from_points(start: (i32, i32), end: (i32, i32)) -> Self {
+		Self::new(
+			start.0.min(end.0),
+			start.1.min(end.1),
+			(start.0 - end.0).unsigned_abs(),
+			(start.1 - end.1).unsigned_abs(),
+		)
+	}
+
+	/**
+	 * Get the bounding area of the RGBA pixels that match the predicate.
+	 *
+	 * The predicate is called with the offset of each pixel in the data.
+	 *
+	 * @param  width
+	 * @param  height
+	 * @param  predicate
+	 * @return Geometry (Option)
+	 */
+	pub fn get_bounds<Predicate: Fn(usize) -> bool>(
+		width: u32,
+		height: u32,
+		predicate: Predicate,
+	) -> Option<Self> {
+		let mut bounds: Option<(u32, u32, u32, u32)> = None;
+		for y in 0..height {
+			for x in 0..width {
+				if predicate(((y * width + x) * 4) as usize) {
+					bounds = Some(match bounds {
+						Some((x1, y1, x2, y2)) => {
+							(x1.min(x), y1.min(y), x2.max(x), y2.max(y))
+						}
+						None => (x, y, x, y),
+					});
+				}
+			}
+		}
+		bounds.map(|(x1, y1, x2, y2)| {
+			Self::new(x1 as i32, y1 as i32, x2 - x1 + 1, y2 - y1 + 1)
+		})
+	}
+
 	/**
 	 * Check if width and height values are zero.
 	 *
@@ -114,5 +163,15 @@ mod tests {
 		let values = "45x28";
 		let geometry = Geometry::parse(values);
 		assert_eq!(values, geometry.to_string());
+		assert_eq!(
+			Geometry::new(5, 10, 20, 30),
+			Geometry::from_points((25, 10), (5, 40))
+		);
+		let data = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1];
+		assert_eq!(
+			Some(Geometry::new(0, 1, 2, 1)),
+			Geometry::get_bounds(2, 2, |i| data[i + 3] != 0)
+		);
+		assert_eq!(None, Geometry::get_bounds(2, 2, |_| false));
 	}
 }
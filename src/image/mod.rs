@@ -4,7 +4,9 @@ pub mod settings;
 use std::fmt;
 
 use crate::image::geometry::Geometry;
-use image::{Bgra, ExtendedColorType};
+use image::imageops::{self, FilterType};
+use image::{Bgra, ExtendedColorType, RgbaImage};
+use std::convert::TryFrom;
 #[cfg(feature = "ski")]
 use {
 	imgref::{Img, ImgVec},
@@ -21,6 +23,7 @@ pub struct Image {
 	data: Vec<Bgra<u8>>,
 	alpha_channel: bool,
 	pub geometry: Geometry,
+	pub delay: Option<u32>,
 }
 
 /* Debug implementation for programmer-facing output */
@@ -52,9 +55,51 @@ impl Image {
 			data,
 			alpha_channel,
 			geometry,
+			delay: None,
 		}
 	}
 
+	/**
+	 * Create a new Image object from RGBA pixels.
+	 *
+	 * @param  data
+	 * @param  geometry
+	 * @return Image
+	 */
+	pub fn from_rgba(data: &[u8], geometry: Geometry) -> Self {
+		Self::new(
+			data.chunks(4)
+				.map(|rgba| Bgra::from([rgba[2], rgba[1], rgba[0], rgba[3]]))
+				.collect(),
+			true,
+			geometry,
+		)
+	}
+
+	/**
+	 * Set the delay (in milliseconds) for showing the image as a frame.
+	 *
+	 * @param  delay (Option)
+	 * @return Image
+	 */
+	pub fn with_delay(mut self, delay: Option<u32>) -> Self {
+		self.delay = delay;
+		self
+	}
+
+	/**
+	 * Get the delay (in milliseconds) for showing the image as a frame.
+	 *
+	 * The images without delays are assumed to be captured at the FPS value.
+	 *
+	 * @param  fps
+	 * @return f32
+	 */
+	pub fn get_delay(&self, fps: u32) -> f32 {
+		self.delay
+			.map_or(1e3 / fps.max(1) as f32, |delay| delay as f32)
+	}
+
 	/**
 	 * Get image data in the given color type.
 	 *
@@ -86,6 +131,53 @@ impl Image {
 		})
 	}
 
+	/**
+	 * Set the color of the pixel at the given position (if it exists).
+	 *
+	 * @param x
+	 * @param y
+	 * @param color
+	 */
+	pub fn set_pixel(&mut self, x: i32, y: i32, color: Bgra<u8>) {
+		if let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) {
+			if x < self.geometry.width && y < self.geometry.height {
+				if let Some(pixel) = self.data.get_mut(
+					(y as usize) * (self.geometry.width as usize) + (x as usize),
+				) {
+					*pixel = color;
+				}
+			}
+		}
+	}
+
+	/**
+	 * Resize the image to the given size.
+	 *
+	 * @param  width
+	 * @param  height
+	 * @param  filter
+	 * @return Image
+	 */
+	pub fn resize(self, width: u32, height: u32, filter: FilterType) -> Self {
+		match RgbaImage::from_raw(
+			self.geometry.width,
+			self.geometry.height,
+			self.data.iter().flat_map(|bgra| bgra.0.to_vec()).collect(),
+		) {
+			Some(buffer) => Self::new(
+				imageops::resize(&buffer, width, height, filter)
+					.into_vec()
+					.chunks(4)
+					.map(|bgra| Bgra::from([bgra[0], bgra[1], bgra[2], bgra[3]]))
+					.collect(),
+				self.alpha_channel,
+				Geometry::new(self.geometry.x, self.geometry.y, width, height),
+			)
+			.with_delay(self.delay),
+			None => self,
+		}
+	}
+
 	/**
 	 * Get an Img Vector from the image data.
 	 *
@@ -123,7 +215,7 @@ mod tests {
 			Bgra::from([128, 128, 128, 0]),
 			Bgra::from([255, 255, 255, 0]),
 		];
-		let image = Image::new(data.to_vec(), false, geometry);
+		let mut image = Image::new(data.to_vec(), false, geometry);
 		assert_eq!(
 			format!("{:?}", image),
 			"Image { data_len: 2, alpha_channel: false, \
@@ -139,5 +231,25 @@ mod tests {
 		assert_eq!(255, image.get_data(ExtendedColorType::Rgb8)[4]);
 		assert_eq!(255, image.get_data(ExtendedColorType::Rgba8)[5]);
 		assert_eq!(128, image.get_data(ExtendedColorType::Rgba16)[5]);
+		image.set_pixel(1, 0, Bgra::from([0, 0, 0, 0]));
+		image.set_pixel(-1, 0, Bgra::from([1, 1, 1, 1]));
+		assert_eq!(0, image.get_data(ExtendedColorType::Rgb8)[4]);
+		let image = Image::new(
+			vec![Bgra::from([10, 20, 30, 255]); 16],
+			false,
+			Geometry::new(5, 5, 4, 4),
+		)
+		.with_delay(Some(40))
+		.resize(2, 1, FilterType::Nearest);
+		assert_eq!(Geometry::new(5, 5, 2, 1), image.geometry);
+		assert_eq!(Some(40), image.delay);
+		assert_eq!(40., image.get_delay(10));
+		assert_eq!(100., image.clone().with_delay(None).get_delay(10));
+		assert_eq!(
+			vec![30, 20, 10, 30, 20, 10],
+			image.get_data(ExtendedColorType::Rgb8)
+		);
+		let image = Image::from_rgba(&[1, 2, 3, 4], Geometry::new(0, 0, 1, 1));
+		assert_eq!(vec![1, 2, 3, 4], image.get_data(ExtendedColorType::Rgba8));
 	}
 }
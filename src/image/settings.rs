@@ -1,5 +1,6 @@
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
+use crate::file::format::FileFormat;
 use image::codecs::png::CompressionType;
 use image::codecs::png::FilterType;
 use image::pnm::{PnmSubtype, SampleEncoding};
@@ -40,10 +41,18 @@ impl PngSettings {
 	 * Create a new PngSettings object from arguments.
 	 *
 	 * @param  matches
+	 * @param  file_format
 	 * @return PngSettings
 	 */
-	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
-		Self::from_parser(ArgParser::from_subcommand(matches, "png"))
+	pub fn from_args(matches: &ArgMatches<'_>, file_format: &FileFormat) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(
+			matches,
+			match file_format {
+				FileFormat::Apng if matches.is_present("make") => "make",
+				FileFormat::Apng => "apng",
+				_ => "png",
+			},
+		))
 	}
 
 	/**
@@ -9,13 +9,19 @@ mod anim;
 mod apng;
 mod app;
 mod args;
+mod daemon;
 mod edit;
 mod file;
 mod gif;
 mod image;
+mod measure;
+mod montage;
+mod optimize;
+mod pick;
 mod record;
 mod settings;
 mod util;
+mod vnc;
 mod window;
 #[cfg(not(all(unix, not(target_os = "macos"))))]
 mod ws;
@@ -26,11 +32,16 @@ use self::args::matches::ArgMatches;
 use self::args::Args;
 use self::settings::AppSettings;
 use self::util::logger::Logger;
-use self::window::Access;
+use self::vnc::VncSystem;
+use self::window::pattern::PatternSystem;
+use self::window::{Access, Capture};
 #[cfg(not(all(unix, not(target_os = "macos"))))]
 use self::ws::WindowSystem;
 #[cfg(all(unix, not(target_os = "macos")))]
+use self::x11::server::XServer;
+#[cfg(all(unix, not(target_os = "macos")))]
 use self::x11::WindowSystem;
+use std::fmt::Debug;
 
 fn main() -> AppResult {
 	let args = Args::parse();
@@ -40,8 +51,39 @@ fn main() -> AppResult {
 		.init()
 		.expect("Failed to initialize the logger");
 	settings.check();
+	#[cfg(all(unix, not(target_os = "macos")))]
+	let _server = match settings.record.server {
+		Some(server) if settings.window_required => {
+			Some(XServer::start(server).expect("Failed to start the X server"))
+		}
+		_ => None,
+	};
+	if settings.record.pattern.is_some() {
+		start(&settings, PatternSystem::init)
+	} else if settings.record.vnc.is_some() {
+		start(&settings, VncSystem::init)
+	} else {
+		start(&settings, WindowSystem::init)
+	}
+}
+
+/**
+ * Start the application using the given window system.
+ *
+ * @param  settings
+ * @param  init
+ * @return Result
+ */
+fn start<'a, Window, System>(
+	settings: &'a AppSettings<'a>,
+	init: fn(&'a AppSettings<'a>) -> Option<System>,
+) -> AppResult
+where
+	Window: Capture + Send + Sync + Copy + Debug + 'static,
+	System: Access<'a, Window>,
+{
 	let window = if settings.window_required {
-		if let Some(window) = WindowSystem::init(&settings)
+		if let Some(window) = init(settings)
 			.expect("Failed to access the window system")
 			.get_window()
 		{
@@ -52,5 +94,5 @@ fn main() -> AppResult {
 	} else {
 		None
 	};
-	App::new(window, &settings).start()
+	App::new(window, settings).start()
 }
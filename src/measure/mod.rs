@@ -0,0 +1,212 @@
+pub mod settings;
+
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use image::Bgra;
+use std::convert::TryFrom;
+use std::fmt;
+
+/* Offset of the label from the end point */
+const LABEL_OFFSET: i32 = 10;
+/* Padding around the label text */
+const LABEL_PADDING: i32 = 2;
+
+/* Measurement between two points or of an area */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+	pub start: (i32, i32),
+	pub end: (i32, i32),
+	pub area: bool,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Measurement {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} {:.1}px {:.1}deg",
+			self.get_geometry(),
+			self.get_distance(),
+			self.get_angle()
+		)
+	}
+}
+
+impl Measurement {
+	/**
+	 * Create a new Measurement object.
+	 *
+	 * @param  start
+	 * @param  end
+	 * @param  area
+	 * @return Measurement
+	 */
+	pub fn new(start: (i32, i32), end: (i32, i32), area: bool) -> Self {
+		Self { start, end, area }
+	}
+
+	/**
+	 * Get the geometry of the area between points.
+	 *
+	 * @return Geometry
+	 */
+	pub fn get_geometry(&self) -> Geometry {
+		Geometry::from_points(self.start, self.end)
+	}
+
+	/**
+	 * Get the distance between points.
+	 *
+	 * @return f64
+	 */
+	pub fn get_distance(&self) -> f64 {
+		f64::from(self.end.0 - self.start.0)
+			.hypot(f64::from(self.end.1 - self.start.1))
+	}
+
+	/**
+	 * Get the angle of the line between points (counterclockwise).
+	 *
+	 * @return f64
+	 */
+	pub fn get_angle(&self) -> f64 {
+		f64::from(self.start.1 - self.end.1)
+			.atan2(f64::from(self.end.0 - self.start.0))
+			.to_degrees()
+	}
+
+	/**
+	 * Get the points of the line or the area borders.
+	 *
+	 * @return Vector of (i32, i32)
+	 */
+	pub fn get_points(&self) -> Vec<(i32, i32)> {
+		let mut points = Vec::new();
+		if self.area {
+			let geometry = self.get_geometry();
+			let (right, bottom) = (
+				geometry.x + i32::try_from(geometry.width).unwrap_or_default(),
+				geometry.y + i32::try_from(geometry.height).unwrap_or_default(),
+			);
+			for x in geometry.x..=right {
+				points.push((x, geometry.y));
+				points.push((x, bottom));
+			}
+			for y in geometry.y..=bottom {
+				points.push((geometry.x, y));
+				points.push((right, y));
+			}
+		} else {
+			let (dx, dy) = (
+				(self.end.0 - self.start.0).abs(),
+				-(self.end.1 - self.start.1).abs(),
+			);
+			let step_x = if self.start.0 < self.end.0 { 1 } else { -1 };
+			let step_y = if self.start.1 < self.end.1 { 1 } else { -1 };
+			let (mut x, mut y, mut error) = (self.start.0, self.start.1, dx + dy);
+			loop {
+				points.push((x, y));
+				if (x, y) == self.end {
+					break;
+				}
+				let double_error = 2 * error;
+				if double_error >= dy {
+					error += dy;
+					x += step_x;
+				}
+				if double_error <= dx {
+					error += dx;
+					y += step_y;
+				}
+			}
+		}
+		points
+	}
+
+	/**
+	 * Draw the measurement and its label on the image.
+	 *
+	 * @param image
+	 * @param color
+	 * @param label (Option)
+	 */
+	pub fn annotate(
+		&self,
+		image: &mut Image,
+		color: Bgra<u8>,
+		label: Option<(Geometry, Vec<bool>)>,
+	) {
+		let (offset_x, offset_y) = (image.geometry.x, image.geometry.y);
+		for (x, y) in self.get_points() {
+			image.set_pixel(x - offset_x, y - offset_y, color);
+		}
+		if let Some((size, mask)) = label {
+			let width = i32::try_from(size.width).unwrap_or_default();
+			let height = i32::try_from(size.height).unwrap_or_default();
+			let max_x = i32::try_from(image.geometry.width).unwrap_or_default()
+				- width - 2 * LABEL_PADDING;
+			let max_y = i32::try_from(image.geometry.height).unwrap_or_default()
+				- height - 2 * LABEL_PADDING;
+			let x = (self.end.0 - offset_x + LABEL_OFFSET).min(max_x).max(0);
+			let y = (self.end.1 - offset_y + LABEL_OFFSET).min(max_y).max(0);
+			for label_y in 0..(height + 2 * LABEL_PADDING) {
+				for label_x in 0..(width + 2 * LABEL_PADDING) {
+					let (text_x, text_y) =
+						(label_x - LABEL_PADDING, label_y - LABEL_PADDING);
+					let is_text = (0..width).contains(&text_x)
+						&& (0..height).contains(&text_y)
+						&& mask
+							.get((text_y * width + text_x) as usize)
+							.copied()
+							.unwrap_or(false);
+					image.set_pixel(
+						x + label_x,
+						y + label_y,
+						if is_text {
+							color
+						} else {
+							Bgra::from([0, 0, 0, 255])
+						},
+					);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::ExtendedColorType;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_measurement() {
+		let measurement = Measurement::new((10, 20), (40, 60), false);
+		assert_eq!(Geometry::new(10, 20, 30, 40), measurement.get_geometry());
+		assert_eq!(50., measurement.get_distance());
+		assert_eq!("30x40 50.0px -53.1deg", measurement.to_string());
+		assert_eq!(90., Measurement::new((0, 10), (0, 0), false).get_angle());
+		assert_eq!(180., Measurement::new((10, 0), (0, 0), false).get_angle());
+		assert_eq!(
+			vec![(0, 0), (1, 1), (2, 1), (3, 2)],
+			Measurement::new((0, 0), (3, 2), false).get_points()
+		);
+		assert_eq!(8, Measurement::new((1, 1), (0, 0), true).get_points().len());
+		let mut image = Image::new(
+			vec![Bgra::from([0, 0, 0, 0]); 64],
+			false,
+			Geometry::new(0, 0, 8, 8),
+		);
+		let color = Bgra::from([0, 0, 255, 255]);
+		Measurement::new((0, 0), (7, 7), false).annotate(
+			&mut image,
+			color,
+			Some((Geometry::new(0, 0, 1, 1), vec![true])),
+		);
+		let data = image.get_data(ExtendedColorType::Rgb8);
+		assert_eq!(vec![255, 0, 0], data[0..3].to_vec());
+		assert_eq!(vec![0, 0, 0], data[3..6].to_vec());
+		assert_eq!(vec![255, 0, 0], data[(5 * 8 + 5) * 3..][..3].to_vec());
+		assert_eq!(vec![0, 0, 0], data[(6 * 8 + 6) * 3..][..3].to_vec());
+	}
+}
@@ -0,0 +1,58 @@
+use crate::args::matches::ArgMatches;
+use crate::args::parser::ArgParser;
+
+/* Measurement settings */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MeasureSettings {
+	pub annotate: bool,
+}
+
+impl MeasureSettings {
+	/**
+	 * Create a new MeasureSettings object.
+	 *
+	 * @param  annotate
+	 * @return MeasureSettings
+	 */
+	pub fn new(annotate: bool) -> Self {
+		Self { annotate }
+	}
+
+	/**
+	 * Create a new MeasureSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return MeasureSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(matches, "measure"))
+	}
+
+	/**
+	 * Create a MeasureSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return MeasureSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(matches) => Self::new(!matches.subcommand().0.is_empty()),
+			None => Self::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, SubCommand};
+	#[test]
+	fn test_measure_settings() {
+		let app = App::new("test").subcommand(SubCommand::with_name("png"));
+		let args = app.clone().get_matches_from(vec!["test", "png"]);
+		assert!(MeasureSettings::from_parser(ArgParser::from_args(&args)).annotate);
+		let args = app.get_matches_from(vec!["test"]);
+		assert!(!MeasureSettings::from_parser(ArgParser::from_args(&args)).annotate);
+		assert!(!MeasureSettings::default().annotate);
+	}
+}
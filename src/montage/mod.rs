@@ -0,0 +1,443 @@
+pub mod settings;
+
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use crate::montage::settings::MontageSettings;
+use crate::window::pattern::DIGITS;
+use image::codecs::png::PngDecoder;
+use image::gif::GifDecoder;
+use image::imageops;
+use image::io::Reader;
+use image::{AnimationDecoder, Frames, ImageFormat, RgbaImage};
+use std::fs;
+use std::io::{Cursor, Error, ErrorKind};
+use std::str::FromStr;
+
+/* Bitmaps of the letters (3x5) for the labels */
+const LETTERS: [u16; 26] = [
+	0b010_101_111_101_101,
+	0b110_101_110_101_110,
+	0b011_100_100_100_011,
+	0b110_101_101_101_110,
+	0b111_100_110_100_111,
+	0b111_100_110_100_100,
+	0b011_100_101_101_011,
+	0b101_101_111_101_101,
+	0b111_010_010_010_111,
+	0b001_001_001_101_010,
+	0b101_101_110_101_101,
+	0b100_100_100_100_111,
+	0b101_111_111_101_101,
+	0b110_101_101_101_101,
+	0b010_101_101_101_010,
+	0b110_101_110_100_100,
+	0b010_101_101_110_011,
+	0b110_101_110_101_101,
+	0b011_100_010_001_110,
+	0b111_010_010_010_010,
+	0b101_101_101_101_111,
+	0b101_101_101_101_010,
+	0b101_101_111_111_101,
+	0b101_101_010_101_101,
+	0b101_101_010_010_010,
+	0b111_001_010_100_111,
+];
+/* Bitmaps of the symbols (3x5) for the labels */
+const SYMBOLS: [(char, u16); 9] = [
+	(' ', 0),
+	('.', 0b000_000_000_000_010),
+	('-', 0b000_000_111_000_000),
+	('_', 0b000_000_000_000_111),
+	(':', 0b000_010_000_010_000),
+	('/', 0b001_001_010_100_100),
+	('#', 0b101_111_101_111_101),
+	('(', 0b010_100_100_100_010),
+	(')', 0b010_001_001_001_010),
+];
+/* Bitmap of the unknown characters */
+const UNKNOWN: u16 = 0b111_001_010_000_010;
+/* Scale of the label text */
+const LABEL_SCALE: u32 = 2;
+/* Padding around the label text */
+const LABEL_PADDING: u32 = 3;
+
+/* Label of the montage tiles */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileLabel {
+	Name,
+	Time,
+}
+
+/* Implementation for parsing TileLabel from a string */
+impl FromStr for TileLabel {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"name" => Ok(Self::Name),
+			"time" => Ok(Self::Time),
+			_ => Err("Unrecognized label"),
+		}
+	}
+}
+
+/* Grid composer for images and animation frames */
+#[derive(Debug)]
+pub struct Montage<'a> {
+	settings: &'a MontageSettings,
+}
+
+impl<'a> Montage<'a> {
+	/**
+	 * Create a new Montage object.
+	 *
+	 * @param  settings
+	 * @return Montage
+	 */
+	pub fn new(settings: &'a MontageSettings) -> Self {
+		Self { settings }
+	}
+
+	/**
+	 * Get the bitmap of a character.
+	 *
+	 * @param  c
+	 * @return u16
+	 */
+	fn get_glyph(c: char) -> u16 {
+		let c = c.to_ascii_uppercase();
+		match c {
+			'0'..='9' => DIGITS[c as usize - '0' as usize],
+			'A'..='Z' => LETTERS[c as usize - 'A' as usize],
+			_ => SYMBOLS
+				.iter()
+				.find(|(symbol, _)| *symbol == c)
+				.map_or(UNKNOWN, |(_, glyph)| *glyph),
+		}
+	}
+
+	/**
+	 * Draw the text on the RGBA pixels.
+	 *
+	 * @param  pixels
+	 * @param  width
+	 * @param  text
+	 * @param  position
+	 * @param  color
+	 */
+	fn draw_text(
+		pixels: &mut [u8],
+		width: u32,
+		text: &str,
+		position: (u32, u32),
+		color: [u8; 3],
+	) {
+		for (i, c) in text.chars().enumerate() {
+			let glyph = Self::get_glyph(c);
+			for bit in 0..15 {
+				if (glyph >> (14 - bit)) & 1 == 0 {
+					continue;
+				}
+				let x = position.0 + (i as u32 * 4 + bit % 3) * LABEL_SCALE;
+				let y = position.1 + (bit / 3) * LABEL_SCALE;
+				for (dx, dy) in (0..LABEL_SCALE)
+					.flat_map(|dx| (0..LABEL_SCALE).map(move |dy| (dx, dy)))
+				{
+					let index = (((y + dy) * width + x + dx) * 4) as usize;
+					if let Some(pixel) = pixels.get_mut(index..index + 3) {
+						pixel.copy_from_slice(&color);
+					}
+				}
+			}
+		}
+	}
+
+	/**
+	 * Format the time (in milliseconds) as a label.
+	 *
+	 * @param  time
+	 * @return String
+	 */
+	fn format_time(time: u32) -> String {
+		format!(
+			"{}:{:02}.{:03}",
+			time / 60000,
+			time / 1000 % 60,
+			time % 1000
+		)
+	}
+
+	/**
+	 * Select the frames of an animation.
+	 *
+	 * @param  frames
+	 * @param  name
+	 * @return Vector of tuple (RgbaImage, String)
+	 */
+	fn select_frames(
+		&self,
+		frames: Frames<'_>,
+		name: &str,
+	) -> Result<Vec<(RgbaImage, String)>, Error> {
+		let frames = frames
+			.collect_frames()
+			.map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+		let mut time = 0;
+		let frames = frames
+			.into_iter()
+			.enumerate()
+			.map(|(i, frame)| {
+				let start = time;
+				let (numer, denom) = frame.delay().numer_denom_ms();
+				time += numer / denom.max(1);
+				(i, start, frame.into_buffer())
+			})
+			.collect::<Vec<(usize, u32, RgbaImage)>>();
+		let count = match self.settings.frames as usize {
+			0 => frames.len(),
+			count => count.min(frames.len()),
+		};
+		let indices = (0..count)
+			.map(|i| i * frames.len() / count.max(1))
+			.collect::<Vec<usize>>();
+		Ok(frames
+			.into_iter()
+			.filter(|(i, _, _)| indices.contains(i))
+			.map(|(i, start, buffer)| {
+				(
+					buffer,
+					match self.settings.label {
+						Some(TileLabel::Time) => Self::format_time(start),
+						_ => format!("{} #{}", name, i),
+					},
+				)
+			})
+			.collect())
+	}
+
+	/**
+	 * Read the images (and animation frames) with their labels.
+	 *
+	 * @return Vector of tuple (RgbaImage, String) (Result)
+	 */
+	pub fn get_tiles(&self) -> Result<Vec<(RgbaImage, String)>, Error> {
+		let mut tiles = Vec::new();
+		for path in &self.settings.files {
+			debug!("Reading {:?}", path);
+			let data = fs::read(path)?;
+			let name = path
+				.file_name()
+				.map(|name| name.to_string_lossy().to_string())
+				.unwrap_or_default();
+			let map_err = |e: image::ImageError| {
+				Error::new(ErrorKind::InvalidData, e.to_string())
+			};
+			match image::guess_format(&data) {
+				Ok(ImageFormat::Gif) => tiles.extend(
+					self.select_frames(
+						GifDecoder::new(Cursor::new(&data))
+							.map_err(map_err)?
+							.into_frames(),
+						&name,
+					)?,
+				),
+				Ok(ImageFormat::Png)
+					if PngDecoder::new(Cursor::new(&data))
+						.map_err(map_err)?
+						.is_apng() =>
+				{
+					tiles.extend(
+						self.select_frames(
+							PngDecoder::new(Cursor::new(&data))
+								.map_err(map_err)?
+								.apng()
+								.into_frames(),
+							&name,
+						)?,
+					)
+				}
+				_ => tiles.push((
+					Reader::open(path)?
+						.with_guessed_format()?
+						.decode()
+						.map_err(map_err)?
+						.to_rgba8(),
+					match self.settings.label {
+						Some(TileLabel::Time) => Self::format_time(0),
+						_ => name,
+					},
+				)),
+			}
+		}
+		Ok(tiles)
+	}
+
+	/**
+	 * Compose the tiles into a grid image.
+	 *
+	 * @param  tiles
+	 * @return Image
+	 */
+	pub fn get_image(&self, tiles: Vec<(RgbaImage, String)>) -> Image {
+		let tiles = tiles
+			.into_iter()
+			.map(|(image, label)| match self.settings.tile {
+				Some(tile) => {
+					let ratio = (f64::from(tile.width) / f64::from(image.width()))
+						.min(f64::from(tile.height) / f64::from(image.height()));
+					(
+						imageops::resize(
+							&image,
+							((f64::from(image.width()) * ratio).round() as u32)
+								.max(1),
+							((f64::from(image.height()) * ratio).round() as u32)
+								.max(1),
+							self.settings.filter,
+						),
+						label,
+					)
+				}
+				None => (image, label),
+			})
+			.collect::<Vec<(RgbaImage, String)>>();
+		let cell = self.settings.tile.map_or_else(
+			|| {
+				tiles.iter().fold((0, 0), |cell, (image, _)| {
+					(cell.0.max(image.width()), cell.1.max(image.height()))
+				})
+			},
+			|tile| (tile.width, tile.height),
+		);
+		let label_height = if self.settings.label.is_some() {
+			5 * LABEL_SCALE + 2 * LABEL_PADDING
+		} else {
+			0
+		};
+		let columns = match self.settings.columns {
+			0 => (tiles.len() as f64).sqrt().ceil() as u32,
+			columns => columns,
+		}
+		.max(1);
+		let rows = (tiles.len() as f64 / f64::from(columns)).ceil() as u32;
+		let spacing = self.settings.spacing;
+		let geometry = Geometry::new(
+			0,
+			0,
+			spacing + columns * (cell.0 + spacing),
+			spacing + rows * (cell.1 + label_height + spacing),
+		);
+		let background = self.settings.background;
+		let mut pixels = [background[0], background[1], background[2], 0xFF]
+			.repeat((geometry.width * geometry.height) as usize);
+		let text_color =
+			if background.iter().map(|v| u32::from(*v)).sum::<u32>() > 382 {
+				[0x00, 0x00, 0x00]
+			} else {
+				[0xFF, 0xFF, 0xFF]
+			};
+		for (i, (image, label)) in tiles.iter().enumerate() {
+			let (column, row) = (i as u32 % columns, i as u32 / columns);
+			let (x, y) = (
+				spacing + column * (cell.0 + spacing),
+				spacing + row * (cell.1 + label_height + spacing),
+			);
+			let (offset_x, offset_y) = (
+				x + (cell.0 - image.width().min(cell.0)) / 2,
+				y + (cell.1 - image.height().min(cell.1)) / 2,
+			);
+			for (px, py, pixel) in image.enumerate_pixels() {
+				if px >= cell.0 || py >= cell.1 {
+					continue;
+				}
+				let index = (((offset_y + py) * geometry.width + offset_x + px) * 4)
+					as usize;
+				let alpha = f64::from(pixel[3]) / 255.;
+				for channel in 0..3 {
+					pixels[index + channel] = (f64::from(pixel[channel]) * alpha
+						+ f64::from(pixels[index + channel]) * (1. - alpha))
+						.round() as u8;
+				}
+			}
+			if label_height > 0 {
+				let length = ((cell.0 / LABEL_SCALE + 1) / 4) as usize;
+				let text = label.chars().take(length).collect::<String>();
+				let text_width = (text.chars().count() as u32 * 4).saturating_sub(1)
+					* LABEL_SCALE;
+				Self::draw_text(
+					&mut pixels,
+					geometry.width,
+					&text,
+					(
+						x + (cell.0 - text_width.min(cell.0)) / 2,
+						y + cell.1 + LABEL_PADDING,
+					),
+					text_color,
+				);
+			}
+		}
+		Image::from_rgba(&pixels, geometry)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::ExtendedColorType;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_montage() {
+		assert_eq!(Ok(TileLabel::Name), TileLabel::from_str("name"));
+		assert!(TileLabel::from_str("x").is_err());
+		assert_eq!(DIGITS[7], Montage::get_glyph('7'));
+		assert_eq!(LETTERS[0], Montage::get_glyph('a'));
+		assert_eq!(UNKNOWN, Montage::get_glyph('~'));
+		assert_eq!("1:05.250", Montage::format_time(65250));
+		let settings = MontageSettings {
+			columns: 2,
+			spacing: 1,
+			background: [0, 0, 0],
+			..MontageSettings::default()
+		};
+		let tiles = vec![
+			(
+				RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255])),
+				String::new(),
+			),
+			(
+				RgbaImage::from_pixel(1, 1, image::Rgba([0, 255, 0, 128])),
+				String::new(),
+			),
+			(
+				RgbaImage::from_pixel(2, 1, image::Rgba([0, 0, 255, 255])),
+				String::new(),
+			),
+		];
+		let image = Montage::new(&settings).get_image(tiles.clone());
+		assert_eq!(Geometry::new(0, 0, 7, 7), image.geometry);
+		let data = image.get_data(ExtendedColorType::Rgb8);
+		let get_pixel =
+			|x: usize, y: usize| data[(y * 7 + x) * 3..(y * 7 + x) * 3 + 3].to_vec();
+		assert_eq!(vec![0, 0, 0], get_pixel(0, 0));
+		assert_eq!(vec![255, 0, 0], get_pixel(1, 1));
+		assert_eq!(vec![0, 128, 0], get_pixel(4, 1));
+		assert_eq!(vec![0, 0, 255], get_pixel(1, 4));
+		let settings = MontageSettings {
+			label: Some(TileLabel::Name),
+			tile: Some(Geometry::new(0, 0, 20, 10)),
+			spacing: 0,
+			filter: image::imageops::FilterType::Nearest,
+			..MontageSettings::default()
+		};
+		let image = Montage::new(&settings).get_image(vec![(
+			RgbaImage::from_pixel(40, 40, image::Rgba([255, 0, 0, 255])),
+			String::from("1"),
+		)]);
+		assert_eq!(
+			Geometry::new(0, 0, 20, 10 + 5 * LABEL_SCALE + 2 * LABEL_PADDING),
+			image.geometry
+		);
+		let data = image.get_data(ExtendedColorType::Rgb8);
+		assert_eq!(vec![255, 255, 255], data[..3].to_vec());
+		assert_eq!(vec![255, 0, 0], data[10 * 3..10 * 3 + 3].to_vec());
+		assert!(data[10 * 20 * 3..].contains(&0));
+	}
+}
@@ -0,0 +1,178 @@
+use crate::args::matches::ArgMatches;
+use crate::args::parser::ArgParser;
+use crate::edit::settings::ImageSettings;
+use crate::image::geometry::Geometry;
+use crate::montage::TileLabel;
+use image::imageops::FilterType;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/* Montage (contact sheet) settings */
+#[derive(Debug)]
+pub struct MontageSettings {
+	pub files: Vec<PathBuf>,
+	pub frames: u32,
+	pub columns: u32,
+	pub spacing: u32,
+	pub background: [u8; 3],
+	pub label: Option<TileLabel>,
+	pub tile: Option<Geometry>,
+	pub filter: FilterType,
+}
+
+/* Default initialization values for MontageSettings */
+impl Default for MontageSettings {
+	fn default() -> Self {
+		Self {
+			files: Vec::new(),
+			frames: 0,
+			columns: 0,
+			spacing: 4,
+			background: [0xFF, 0xFF, 0xFF],
+			label: None,
+			tile: None,
+			filter: FilterType::Lanczos3,
+		}
+	}
+}
+
+impl MontageSettings {
+	/**
+	 * Create a new MontageSettings object.
+	 *
+	 * @param  files
+	 * @param  frames
+	 * @param  columns
+	 * @param  spacing
+	 * @param  background
+	 * @param  label (Option)
+	 * @param  tile (Option)
+	 * @param  filter
+	 * @return MontageSettings
+	 */
+	#[allow(clippy::too_many_arguments)]
+	pub fn new(
+		files: Vec<PathBuf>,
+		frames: u32,
+		columns: u32,
+		spacing: u32,
+		background: [u8; 3],
+		label: Option<TileLabel>,
+		tile: Option<Geometry>,
+		filter: FilterType,
+	) -> Self {
+		Self {
+			files,
+			frames,
+			columns,
+			spacing,
+			background,
+			label,
+			tile,
+			filter,
+		}
+	}
+
+	/**
+	 * Create a new MontageSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return MontageSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(matches, "montage"))
+	}
+
+	/**
+	 * Create a MontageSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return MontageSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(ref matches) => Self::new(
+				matches
+					.values_of("files")
+					.map(|files| files.map(PathBuf::from).collect())
+					.unwrap_or_default(),
+				parser.parse("frames", Self::default().frames),
+				parser.parse("columns", Self::default().columns),
+				parser.parse("spacing", Self::default().spacing),
+				matches
+					.value_of("background")
+					.and_then(|color| u32::from_str_radix(color, 16).ok())
+					.map_or(Self::default().background, |color| {
+						[(color >> 16) as u8, (color >> 8) as u8, color as u8]
+					}),
+				matches
+					.value_of("label")
+					.and_then(|label| TileLabel::from_str(label).ok()),
+				matches
+					.value_of("tile")
+					.map(Geometry::parse)
+					.filter(|tile| tile.width > 0 && tile.height > 0),
+				ImageSettings::parse_filter(matches.value_of("filter")),
+			),
+			None => Self::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, Arg};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_montage_settings() {
+		let args = App::new("test")
+			.arg(Arg::with_name("files").multiple(true))
+			.arg(Arg::with_name("frames").long("frames").takes_value(true))
+			.arg(Arg::with_name("columns").long("columns").takes_value(true))
+			.arg(Arg::with_name("spacing").long("spacing").takes_value(true))
+			.arg(
+				Arg::with_name("background")
+					.long("background")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("label").long("label").takes_value(true))
+			.arg(Arg::with_name("tile").long("tile").takes_value(true))
+			.arg(Arg::with_name("filter").long("filter").takes_value(true))
+			.get_matches_from(vec![
+				"test",
+				"a.png",
+				"b.gif",
+				"--frames",
+				"6",
+				"--columns",
+				"3",
+				"--spacing",
+				"2",
+				"--background",
+				"1a2b3c",
+				"--label",
+				"time",
+				"--tile",
+				"160x90",
+				"--filter",
+				"nearest",
+			]);
+		let montage_settings =
+			MontageSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(
+			vec![PathBuf::from("a.png"), PathBuf::from("b.gif")],
+			montage_settings.files
+		);
+		assert_eq!(6, montage_settings.frames);
+		assert_eq!(3, montage_settings.columns);
+		assert_eq!(2, montage_settings.spacing);
+		assert_eq!([0x1A, 0x2B, 0x3C], montage_settings.background);
+		assert_eq!(Some(TileLabel::Time), montage_settings.label);
+		assert_eq!(Some(Geometry::new(0, 0, 160, 90)), montage_settings.tile);
+		assert_eq!("Nearest", format!("{:?}", montage_settings.filter));
+		let montage_settings = MontageSettings::default();
+		assert_eq!(4, montage_settings.spacing);
+		assert_eq!(None, montage_settings.label);
+	}
+}
@@ -0,0 +1,518 @@
+pub mod settings;
+
+use crate::anim::AnimFormat;
+use crate::apng::writer::ApngWriter;
+use crate::image::geometry::Geometry;
+use crate::image::settings::PngSettings;
+use crate::optimize::settings::OptimizeSettings;
+use apng::{BlendOp, DisposeOp, Frame as ApngFrame};
+use color_quant::NeuQuant;
+use gif::{DisposalMethod, Encoder, Frame as GifFrame, Repeat};
+use image::png::{CompressionType, FilterType};
+use image::Frame;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::io::Write;
+
+/* Sampling factor of the color quantizer (1-30) */
+const QUANT_SPEED: i32 = 10;
+
+/* Frame that only contains the changed area of the animation */
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeltaFrame {
+	pub geometry: Geometry,
+	pub data: Vec<u8>,
+	pub delay: u32,
+	pub dispose: bool,
+}
+
+impl DeltaFrame {
+	/**
+	 * Create a new DeltaFrame object.
+	 *
+	 * @param  geometry
+	 * @param  data
+	 * @param  delay
+	 * @return DeltaFrame
+	 */
+	pub fn new(geometry: Geometry, data: Vec<u8>, delay: u32) -> Self {
+		Self {
+			geometry,
+			data,
+			delay,
+			dispose: false,
+		}
+	}
+
+	/**
+	 * Expand the frame to the given area with transparent pixels.
+	 *
+	 * @param area
+	 */
+	fn expand(&mut self, area: Geometry) {
+		let mut data = vec![0; (area.width * area.height * 4) as usize];
+		let width = self.geometry.width as usize * 4;
+		for (y, row) in self.data.chunks(width).enumerate() {
+			let offset = ((self.geometry.y - area.y) as usize + y)
+				* area.width as usize
+				+ (self.geometry.x - area.x) as usize;
+			data[offset * 4..offset * 4 + width].copy_from_slice(row);
+		}
+		self.geometry = area;
+		self.data = data;
+	}
+
+	/**
+	 * Check if the frame has transparent pixels.
+	 *
+	 * @return bool
+	 */
+	fn has_transparency(&self) -> bool {
+		self.data.chunks(4).any(|pixel| pixel[3] == 0)
+	}
+}
+
+/* Animation optimizer */
+#[derive(Debug)]
+pub struct AnimOptimizer<'a> {
+	width: u32,
+	height: u32,
+	format: AnimFormat,
+	settings: &'a OptimizeSettings,
+}
+
+impl<'a> AnimOptimizer<'a> {
+	/**
+	 * Create a new AnimOptimizer object.
+	 *
+	 * @param  size
+	 * @param  format
+	 * @param  settings
+	 * @return AnimOptimizer
+	 */
+	pub fn new(
+		size: (u32, u32),
+		format: AnimFormat,
+		settings: &'a OptimizeSettings,
+	) -> Self {
+		Self {
+			width: size.0,
+			height: size.1,
+			format,
+			settings,
+		}
+	}
+
+	/**
+	 * Get the RGBA pixels of the frame that can be represented in the format.
+	 *
+	 * @param  frame
+	 * @return Vector of u8
+	 */
+	fn get_pixels(&self, frame: &Frame) -> Vec<u8> {
+		let mut pixels = frame.buffer().to_vec();
+		for pixel in pixels.chunks_mut(4) {
+			if self.format == AnimFormat::Gif {
+				pixel[3] = if pixel[3] < 128 { 0 } else { 255 };
+			}
+			if pixel[3] == 0 {
+				pixel.copy_from_slice(&[0; 4]);
+			}
+		}
+		pixels
+	}
+
+	/**
+	 * Check if the pixel is changed within the fuzz tolerance.
+	 *
+	 * @param  pixel1
+	 * @param  pixel2
+	 * @return bool
+	 */
+	fn is_changed(&self, pixel1: &[u8], pixel2: &[u8]) -> bool {
+		let tolerance = i16::from(self.settings.fuzz) * 255 / 100;
+		pixel1
+			.iter()
+			.zip(pixel2)
+			.any(|(c1, c2)| (i16::from(*c1) - i16::from(*c2)).abs() > tolerance)
+	}
+
+	/**
+	 * Get the union of the given areas.
+	 *
+	 * @param  area1
+	 * @param  area2
+	 * @return Geometry
+	 */
+	fn get_union(area1: Geometry, area2: Geometry) -> Geometry {
+		let (x, y) = (area1.x.min(area2.x), area1.y.min(area2.y));
+		Geometry::new(
+			x,
+			y,
+			((area1.x + area1.width as i32).max(area2.x + area2.width as i32) - x)
+				as u32,
+			((area1.y + area1.height as i32).max(area2.y + area2.height as i32) - y)
+				as u32,
+		)
+	}
+
+	/**
+	 * Get the delta frames by cropping the changed areas, reusing the
+	 * unchanged pixels as transparent and merging the duplicate frames.
+	 *
+	 * @param  frames
+	 * @return Vector of DeltaFrame
+	 */
+	pub fn get_frames(&self, frames: Vec<Frame>) -> Vec<DeltaFrame> {
+		let mut canvas = vec![0; (self.width * self.height * 4) as usize];
+		let mut delta_frames: Vec<DeltaFrame> = Vec::new();
+		for frame in frames {
+			let (numerator, denominator) = frame.delay().numer_denom_ms();
+			let delay =
+				(f64::from(numerator) / f64::from(denominator)).round() as u32;
+			let pixels = self.get_pixels(&frame);
+			let clear_area = Geometry::get_bounds(self.width, self.height, |i| {
+				pixels[i + 3] < 255
+					&& canvas[i + 3] != 0
+					&& self.is_changed(&canvas[i..i + 4], &pixels[i..i + 4])
+			});
+			let mut cleared = false;
+			if let (Some(area), Some(previous)) =
+				(clear_area, delta_frames.last_mut())
+			{
+				previous.expand(Self::get_union(previous.geometry, area));
+				previous.dispose = true;
+				let area = previous.geometry;
+				for y in area.y..area.y + area.height as i32 {
+					let offset =
+						(y as u32 * self.width + area.x as u32) as usize * 4;
+					canvas[offset..offset + area.width as usize * 4]
+						.iter_mut()
+						.for_each(|v| *v = 0);
+				}
+				cleared = true;
+			}
+			match Geometry::get_bounds(self.width, self.height, |i| {
+				self.is_changed(&canvas[i..i + 4], &pixels[i..i + 4])
+			}) {
+				Some(area) => {
+					let mut data = Vec::new();
+					for y in area.y..area.y + area.height as i32 {
+						for x in area.x..area.x + area.width as i32 {
+							let i = (y as u32 * self.width + x as u32) as usize * 4;
+							if self.is_changed(&canvas[i..i + 4], &pixels[i..i + 4])
+							{
+								canvas[i..i + 4].copy_from_slice(&pixels[i..i + 4]);
+								data.extend_from_slice(&pixels[i..i + 4]);
+							} else {
+								data.extend_from_slice(&[0; 4]);
+							}
+						}
+					}
+					delta_frames.push(DeltaFrame::new(area, data, delay));
+				}
+				None => match delta_frames.last_mut() {
+					Some(previous) if !cleared => previous.delay += delay,
+					_ => delta_frames.push(DeltaFrame::new(
+						Geometry::new(0, 0, 1, 1),
+						vec![0; 4],
+						delay,
+					)),
+				},
+			}
+		}
+		if let Some(first) = delta_frames.first_mut() {
+			first.expand(Geometry::new(0, 0, self.width, self.height));
+		}
+		delta_frames
+	}
+
+	/**
+	 * Get the palette of the frame and the indices of its pixels.
+	 *
+	 * @param  frame
+	 * @return Tuple (Vector of u8, Vector of u8, u8 (Option))
+	 */
+	fn get_palette(&self, frame: &DeltaFrame) -> (Vec<u8>, Vec<u8>, Option<u8>) {
+		let transparent = frame.has_transparency();
+		let max_colors =
+			usize::from(self.settings.colors) - usize::from(transparent);
+		let mut colors = HashMap::new();
+		for pixel in frame.data.chunks(4).filter(|pixel| pixel[3] != 0) {
+			let index = colors.len();
+			colors
+				.entry([pixel[0], pixel[1], pixel[2]])
+				.or_insert(index);
+			if colors.len() > max_colors {
+				break;
+			}
+		}
+		let (mut palette, indices) = if colors.len() <= max_colors {
+			let mut palette = vec![0; colors.len() * 3];
+			for (color, index) in &colors {
+				palette[index * 3..index * 3 + 3].copy_from_slice(color);
+			}
+			(
+				palette,
+				frame
+					.data
+					.chunks(4)
+					.map(|pixel| match pixel[3] {
+						0 => colors.len() as u8,
+						_ => colors[&[pixel[0], pixel[1], pixel[2]]] as u8,
+					})
+					.collect::<Vec<u8>>(),
+			)
+		} else {
+			let opaque = frame
+				.data
+				.chunks(4)
+				.filter(|pixel| pixel[3] != 0)
+				.flatten()
+				.copied()
+				.collect::<Vec<u8>>();
+			let quant = NeuQuant::new(QUANT_SPEED, max_colors, &opaque);
+			(
+				quant.color_map_rgb(),
+				frame
+					.data
+					.chunks(4)
+					.map(|pixel| match pixel[3] {
+						0 => max_colors as u8,
+						_ => quant.index_of(pixel) as u8,
+					})
+					.collect(),
+			)
+		};
+		let transparent = if transparent {
+			let index = (palette.len() / 3) as u8;
+			palette.extend_from_slice(&[0; 3]);
+			Some(index)
+		} else {
+			None
+		};
+		(palette, indices, transparent)
+	}
+
+	/**
+	 * Reduce the colors of the RGBA pixels if necessary.
+	 *
+	 * @param  frame
+	 * @return Vector of u8
+	 */
+	fn get_reduced_data(&self, frame: &DeltaFrame) -> Vec<u8> {
+		if self.settings.colors >= 256 {
+			return frame.data.clone();
+		}
+		let mut colors = frame.data.chunks(4).collect::<Vec<&[u8]>>();
+		colors.sort_unstable();
+		colors.dedup();
+		if colors.len() <= usize::from(self.settings.colors) {
+			return frame.data.clone();
+		}
+		let quant =
+			NeuQuant::new(QUANT_SPEED, self.settings.colors.into(), &frame.data);
+		let palette = quant.color_map_rgba();
+		frame
+			.data
+			.chunks(4)
+			.flat_map(|pixel| match pixel[3] {
+				0 => vec![0; 4],
+				_ => {
+					let index = quant.index_of(pixel) * 4;
+					palette[index..index + 4].to_vec()
+				}
+			})
+			.collect()
+	}
+
+	/**
+	 * Encode the frames as GIF.
+	 *
+	 * @param frames
+	 * @param plays
+	 * @param output
+	 */
+	pub fn encode_gif<Output: Write>(
+		&self,
+		frames: &[DeltaFrame],
+		plays: u32,
+		output: Output,
+	) {
+		let mut encoder = Encoder::new(
+			output,
+			self.width.try_into().unwrap_or_default(),
+			self.height.try_into().unwrap_or_default(),
+			&[],
+		)
+		.expect("Failed to create a GIF encoder");
+		if plays != 1 {
+			encoder
+				.set_repeat(match plays {
+					0 => Repeat::Infinite,
+					n => Repeat::Finite((n - 1).try_into().unwrap_or(u16::MAX)),
+				})
+				.expect("Failed to set repeat count");
+		}
+		let mut elapsed = 0;
+		for (i, frame) in frames.iter().enumerate() {
+			let start = (elapsed + 5) / 10;
+			elapsed += frame.delay;
+			let (palette, indices, transparent) = self.get_palette(frame);
+			encoder
+				.write_frame(&GifFrame {
+					delay: ((elapsed + 5) / 10 - start)
+						.try_into()
+						.unwrap_or(u16::MAX),
+					dispose: if frame.dispose {
+						DisposalMethod::Background
+					} else {
+						DisposalMethod::Keep
+					},
+					transparent,
+					left: frame.geometry.x.try_into().unwrap_or_default(),
+					top: frame.geometry.y.try_into().unwrap_or_default(),
+					width: frame.geometry.width.try_into().unwrap_or_default(),
+					height: frame.geometry.height.try_into().unwrap_or_default(),
+					palette: Some(palette),
+					buffer: Cow::Owned(indices),
+					..GifFrame::default()
+				})
+				.unwrap_or_else(|_| {
+					panic!("Failed to write frame: {}/{}", i + 1, frames.len())
+				});
+		}
+	}
+
+	/**
+	 * Encode the frames as APNG.
+	 *
+	 * @param frames
+	 * @param plays
+	 * @param output
+	 */
+	pub fn encode_apng<Output: Write>(
+		&self,
+		frames: &[DeltaFrame],
+		plays: u32,
+		output: Output,
+	) {
+		let settings = PngSettings::new(CompressionType::Best, FilterType::Paeth);
+		let mut writer = ApngWriter::new(
+			output,
+			(self.width, self.height),
+			frames.len().try_into().unwrap_or_default(),
+			plays,
+			&settings,
+		)
+		.expect("Failed to create APNG encoder");
+		for (i, frame) in frames.iter().enumerate() {
+			let (delay_num, delay_den) = match frame.delay.try_into() {
+				Ok(delay) => (delay, 1000),
+				Err(_) => ((frame.delay / 10).try_into().unwrap_or(u16::MAX), 100),
+			};
+			writer
+				.write_frame(
+					&self.get_reduced_data(frame),
+					&ApngFrame {
+						width: Some(frame.geometry.width),
+						height: Some(frame.geometry.height),
+						offset_x: frame.geometry.x.try_into().ok(),
+						offset_y: frame.geometry.y.try_into().ok(),
+						delay_num: Some(delay_num),
+						delay_den: Some(delay_den),
+						dispose_op: Some(if frame.dispose {
+							DisposeOp::ApngDisposeOpBackground
+						} else {
+							DisposeOp::ApngDisposeOpNone
+						}),
+						blend_op: Some(BlendOp::ApngBlendOpOver),
+					},
+				)
+				.unwrap_or_else(|_| {
+					panic!("Failed to write frame: {}/{}", i + 1, frames.len())
+				});
+		}
+		writer.finish().expect("Failed to finish encoding");
+	}
+
+	/**
+	 * Optimize the frames and write them in the format.
+	 *
+	 * @param frames
+	 * @param plays
+	 * @param output
+	 */
+	pub fn save<Output: Write>(
+		&self,
+		frames: Vec<Frame>,
+		plays: u32,
+		output: Output,
+	) {
+		let frame_count = frames.len();
+		let frames = self.get_frames(frames);
+		debug!("Merged frames: {} -> {}", frame_count, frames.len());
+		match self.format {
+			AnimFormat::Gif => self.encode_gif(&frames, plays, output),
+			AnimFormat::Apng => self.encode_apng(&frames, plays, output),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::codecs::gif::GifDecoder;
+	use image::codecs::png::PngDecoder;
+	use image::{AnimationDecoder, Delay, RgbaImage};
+	use pretty_assertions::assert_eq;
+	use std::path::PathBuf;
+	fn get_frame(pixels: &[[u8; 4]; 4]) -> Frame {
+		Frame::from_parts(
+			RgbaImage::from_raw(2, 2, pixels.iter().flatten().copied().collect())
+				.unwrap(),
+			0,
+			0,
+			Delay::from_numer_denom_ms(100, 1),
+		)
+	}
+	#[test]
+	fn test_anim_optimizer() {
+		let (red, blue, none) = ([255, 0, 0, 255], [0, 0, 255, 255], [0; 4]);
+		let frames = vec![
+			get_frame(&[red, red, red, red]),
+			get_frame(&[red, red, red, red]),
+			get_frame(&[red, blue, red, red]),
+			get_frame(&[red, blue, red, none]),
+		];
+		let settings = OptimizeSettings::new(PathBuf::new(), 256, 0);
+		for format in &[AnimFormat::Gif, AnimFormat::Apng] {
+			let optimizer = AnimOptimizer::new((2, 2), *format, &settings);
+			let delta_frames = optimizer.get_frames(frames.clone());
+			assert_eq!(3, delta_frames.len());
+			assert_eq!(200, delta_frames[0].delay);
+			assert_eq!(Geometry::new(1, 0, 1, 2), delta_frames[1].geometry);
+			assert!(delta_frames[1].dispose);
+			assert_eq!(Geometry::new(1, 0, 1, 1), delta_frames[2].geometry);
+			let mut output = Vec::new();
+			optimizer.save(frames.clone(), 3, &mut output);
+			assert_eq!(3, format.get_plays(&output));
+			let decoded = match format {
+				AnimFormat::Gif => GifDecoder::new(output.as_slice())
+					.unwrap()
+					.into_frames()
+					.collect_frames(),
+				AnimFormat::Apng => PngDecoder::new(output.as_slice())
+					.unwrap()
+					.apng()
+					.into_frames()
+					.collect_frames(),
+			}
+			.unwrap();
+			assert_eq!(3, decoded.len());
+			assert_eq!(&blue, &decoded[2].buffer().get_pixel(1, 0).0);
+			assert_eq!(&none, &decoded[2].buffer().get_pixel(1, 1).0);
+		}
+	}
+}
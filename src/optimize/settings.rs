@@ -0,0 +1,108 @@
+use crate::anim::AnimFormat;
+use crate::args::matches::ArgMatches;
+use crate::args::parser::ArgParser;
+use std::path::PathBuf;
+
+/* Animation optimization settings */
+#[derive(Debug)]
+pub struct OptimizeSettings {
+	pub file: PathBuf,
+	pub colors: u16,
+	pub fuzz: u8,
+}
+
+/* Default initialization values for OptimizeSettings */
+impl Default for OptimizeSettings {
+	fn default() -> Self {
+		Self {
+			file: PathBuf::new(),
+			colors: 256,
+			fuzz: 0,
+		}
+	}
+}
+
+impl OptimizeSettings {
+	/**
+	 * Create a new OptimizeSettings object.
+	 *
+	 * @param  file
+	 * @param  colors
+	 * @param  fuzz
+	 * @return OptimizeSettings
+	 */
+	pub fn new(file: PathBuf, colors: u16, fuzz: u8) -> Self {
+		Self { file, colors, fuzz }
+	}
+
+	/**
+	 * Create a new OptimizeSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return OptimizeSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(matches, "optimize"))
+	}
+
+	/**
+	 * Create a OptimizeSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return OptimizeSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(ref matches) => Self::new(
+				PathBuf::from(matches.value_of("file").unwrap_or_default()),
+				parser.parse("colors", Self::default().colors).clamp(2, 256),
+				parser.parse("fuzz", Self::default().fuzz).min(100),
+			),
+			None => Self::default(),
+		}
+	}
+
+	/**
+	 * Get the animation format from the file extension.
+	 *
+	 * @return AnimFormat
+	 */
+	pub fn get_format(&self) -> AnimFormat {
+		match self
+			.file
+			.extension()
+			.and_then(|extension| extension.to_str())
+		{
+			Some(extension) if extension.eq_ignore_ascii_case("gif") => {
+				AnimFormat::Gif
+			}
+			_ => AnimFormat::Apng,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, Arg};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_optimize_settings() {
+		let args = App::new("test")
+			.arg(Arg::with_name("file").required(true))
+			.arg(Arg::with_name("colors").long("colors").takes_value(true))
+			.arg(Arg::with_name("fuzz").long("fuzz").takes_value(true))
+			.get_matches_from(vec![
+				"test", "test.GIF", "--colors", "1", "--fuzz", "120",
+			]);
+		let optimize_settings =
+			OptimizeSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(PathBuf::from("test.GIF"), optimize_settings.file);
+		assert_eq!(2, optimize_settings.colors);
+		assert_eq!(100, optimize_settings.fuzz);
+		assert_eq!(AnimFormat::Gif, optimize_settings.get_format());
+		let optimize_settings = OptimizeSettings::default();
+		assert_eq!(256, optimize_settings.colors);
+		assert_eq!(AnimFormat::Apng, optimize_settings.get_format());
+	}
+}
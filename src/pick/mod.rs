@@ -0,0 +1,143 @@
+pub mod settings;
+
+use hex::ToHex;
+use std::fmt;
+
+/* Color of a pixel along with its position */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelColor {
+	pub x: i32,
+	pub y: i32,
+	pub rgba: [u8; 4],
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for PixelColor {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let (hue, saturation, lightness) = self.get_hsl();
+		write!(
+			f,
+			"{} rgb({}, {}, {}) hsl({}, {}%, {}%)",
+			self.get_hex(),
+			self.rgba[0],
+			self.rgba[1],
+			self.rgba[2],
+			hue,
+			saturation,
+			lightness
+		)
+	}
+}
+
+impl PixelColor {
+	/**
+	 * Create a new PixelColor object.
+	 *
+	 * @param  x
+	 * @param  y
+	 * @param  rgba
+	 * @return PixelColor
+	 */
+	pub fn new(x: i32, y: i32, rgba: [u8; 4]) -> Self {
+		Self { x, y, rgba }
+	}
+
+	/**
+	 * Get the color in hex format. (#RRGGBBAA)
+	 *
+	 * @return String
+	 */
+	pub fn get_hex(&self) -> String {
+		format!("#{}", self.rgba.encode_hex::<String>()).to_uppercase()
+	}
+
+	/**
+	 * Get the hue, saturation and lightness values of the color.
+	 *
+	 * @return Tuple (u16, u8, u8)
+	 */
+	pub fn get_hsl(&self) -> (u16, u8, u8) {
+		let [red, green, blue] = [
+			f64::from(self.rgba[0]) / 255.,
+			f64::from(self.rgba[1]) / 255.,
+			f64::from(self.rgba[2]) / 255.,
+		];
+		let max = red.max(green).max(blue);
+		let min = red.min(green).min(blue);
+		let lightness = (max + min) / 2.;
+		let delta = max - min;
+		let (hue, saturation) = if delta == 0. {
+			(0., 0.)
+		} else {
+			(
+				60. * if (max - red).abs() < f64::EPSILON {
+					((green - blue) / delta).rem_euclid(6.)
+				} else if (max - green).abs() < f64::EPSILON {
+					(blue - red) / delta + 2.
+				} else {
+					(red - green) / delta + 4.
+				},
+				delta / (1. - (2. * lightness - 1.).abs()),
+			)
+		};
+		(
+			hue.round() as u16 % 360,
+			(saturation * 100.).round() as u8,
+			(lightness * 100.).round() as u8,
+		)
+	}
+
+	/**
+	 * Get the color as a JSON object.
+	 *
+	 * @return String
+	 */
+	pub fn get_json(&self) -> String {
+		let (hue, saturation, lightness) = self.get_hsl();
+		format!(
+			"{{\"x\": {}, \"y\": {}, \"hex\": \"{}\", \"rgb\": [{}, {}, {}], \
+			\"alpha\": {}, \"hsl\": [{}, {}, {}]}}",
+			self.x,
+			self.y,
+			self.get_hex(),
+			self.rgba[0],
+			self.rgba[1],
+			self.rgba[2],
+			self.rgba[3],
+			hue,
+			saturation,
+			lightness
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_pixel_color() {
+		let color = PixelColor::new(10, 20, [0x43, 0x3D, 0x2B, 0xFF]);
+		assert_eq!("#433D2BFF", color.get_hex());
+		assert_eq!((45, 22, 22), color.get_hsl());
+		assert_eq!(
+			"#433D2BFF rgb(67, 61, 43) hsl(45, 22%, 22%)",
+			color.to_string()
+		);
+		assert_eq!(
+			"{\"x\": 10, \"y\": 20, \"hex\": \"#433D2BFF\", \"rgb\": [67, 61, 43], \
+			\"alpha\": 255, \"hsl\": [45, 22, 22]}",
+			color.get_json()
+		);
+		for (rgba, hsl) in &[
+			([0, 0, 0, 255], (0, 0, 0)),
+			([255, 255, 255, 255], (0, 0, 100)),
+			([255, 0, 0, 255], (0, 100, 50)),
+			([0, 255, 0, 255], (120, 100, 50)),
+			([0, 0, 255, 255], (240, 100, 50)),
+			([255, 0, 128, 255], (330, 100, 50)),
+		] {
+			assert_eq!(*hsl, PixelColor::new(0, 0, *rgba).get_hsl());
+		}
+	}
+}
@@ -0,0 +1,78 @@
+use crate::args::matches::ArgMatches;
+use crate::args::parser::ArgParser;
+
+/* Color picker settings */
+#[derive(Clone, Copy, Debug)]
+pub struct PickSettings {
+	pub count: usize,
+	pub json: bool,
+}
+
+/* Default initialization values for PickSettings */
+impl Default for PickSettings {
+	fn default() -> Self {
+		Self {
+			count: 1,
+			json: false,
+		}
+	}
+}
+
+impl PickSettings {
+	/**
+	 * Create a new PickSettings object.
+	 *
+	 * @param  count
+	 * @param  json
+	 * @return PickSettings
+	 */
+	pub fn new(count: usize, json: bool) -> Self {
+		Self { count, json }
+	}
+
+	/**
+	 * Create a new PickSettings object from arguments.
+	 *
+	 * @param  matches
+	 * @return PickSettings
+	 */
+	pub fn from_args(matches: &ArgMatches<'_>) -> Self {
+		Self::from_parser(ArgParser::from_subcommand(matches, "pick"))
+	}
+
+	/**
+	 * Create a PickSettings object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return PickSettings
+	 */
+	fn from_parser(parser: ArgParser<'_>) -> Self {
+		match parser.args {
+			Some(ref matches) => Self::new(
+				parser.parse("count", Self::default().count).max(1),
+				matches.is_present("json"),
+			),
+			None => Self::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{App, Arg};
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_pick_settings() {
+		let args = App::new("test")
+			.arg(Arg::with_name("count").long("count").takes_value(true))
+			.arg(Arg::with_name("json").long("json"))
+			.get_matches_from(vec!["test", "--count", "3", "--json"]);
+		let pick_settings = PickSettings::from_parser(ArgParser::from_args(&args));
+		assert_eq!(3, pick_settings.count);
+		assert!(pick_settings.json);
+		let pick_settings = PickSettings::default();
+		assert_eq!(1, pick_settings.count);
+		assert!(!pick_settings.json);
+	}
+}
@@ -0,0 +1,362 @@
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(unix)]
+use {
+	nix::sys::signal::{self, SaFlags, SigAction, SigHandler, SigSet, Signal},
+	std::fs,
+	std::io::{BufRead, BufReader, Write},
+	std::os::raw::c_int,
+	std::os::unix::net::{UnixListener, UnixStream},
+	std::sync::atomic::AtomicI32,
+	std::thread,
+	std::time::Duration,
+};
+
+/* Interval for polling the control socket */
+#[cfg(unix)]
+const SOCKET_POLL_INTERVAL: u64 = 10;
+/* Last signal received by the signal handler */
+#[cfg(unix)]
+static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/* State of the recording */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordState {
+	Waiting,
+	Recording,
+	Paused,
+	Stopped,
+	Cancelled,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for RecordState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", format!("{:?}", self).to_lowercase())
+	}
+}
+
+impl RecordState {
+	/**
+	 * Create a RecordState enum from its numeric value.
+	 *
+	 * @param  value
+	 * @return RecordState
+	 */
+	fn from_value(value: u8) -> Self {
+		match value {
+			0 => Self::Waiting,
+			1 => Self::Recording,
+			2 => Self::Paused,
+			3 => Self::Stopped,
+			_ => Self::Cancelled,
+		}
+	}
+
+	/**
+	 * Get the numeric value of the state.
+	 *
+	 * @return u8
+	 */
+	fn as_value(self) -> u8 {
+		match self {
+			Self::Waiting => 0,
+			Self::Recording => 1,
+			Self::Paused => 2,
+			Self::Stopped => 3,
+			Self::Cancelled => 4,
+		}
+	}
+
+	/**
+	 * Check if the recording is finished.
+	 *
+	 * @return bool
+	 */
+	pub fn is_finished(&self) -> bool {
+		self == &Self::Stopped || self == &Self::Cancelled
+	}
+}
+
+/* External control of the recording via signals and socket */
+#[derive(Debug)]
+pub struct RecordControl {
+	state: AtomicU8,
+	frames: AtomicUsize,
+	socket: Option<PathBuf>,
+}
+
+impl RecordControl {
+	/**
+	 * Create a new RecordControl object.
+	 *
+	 * @param  wait
+	 * @param  socket (Option)
+	 * @return RecordControl
+	 */
+	pub fn new(wait: bool, socket: Option<PathBuf>) -> Self {
+		Self {
+			state: AtomicU8::new(
+				if wait {
+					RecordState::Waiting
+				} else {
+					RecordState::Recording
+				}
+				.as_value(),
+			),
+			frames: AtomicUsize::new(0),
+			socket,
+		}
+	}
+
+	/**
+	 * Get the current state of the recording.
+	 *
+	 * @return RecordState
+	 */
+	pub fn get_state(&self) -> RecordState {
+		RecordState::from_value(self.state.load(Ordering::SeqCst))
+	}
+
+	/**
+	 * Set the state of the recording.
+	 *
+	 * @param state
+	 */
+	pub fn set_state(&self, state: RecordState) {
+		if !self.get_state().is_finished() {
+			debug!("Recording state: {}", state);
+			self.state.store(state.as_value(), Ordering::SeqCst);
+		}
+	}
+
+	/**
+	 * Set the number of captured frames.
+	 *
+	 * @param frames
+	 */
+	pub fn set_frames(&self, frames: usize) {
+		self.frames.store(frames, Ordering::SeqCst);
+	}
+
+	/**
+	 * Get the status of the recording.
+	 *
+	 * @return String
+	 */
+	pub fn get_status(&self) -> String {
+		format!(
+			"{} {}",
+			self.get_state(),
+			self.frames.load(Ordering::SeqCst)
+		)
+	}
+
+	/**
+	 * Handle a control command and return the response.
+	 *
+	 * @param  command
+	 * @return String
+	 */
+	pub fn handle_command(&self, command: &str) -> String {
+		let state = self.get_state();
+		match command.trim() {
+			"start" | "resume" if state != RecordState::Recording => {
+				self.set_state(RecordState::Recording)
+			}
+			"pause" if state == RecordState::Recording => {
+				self.set_state(RecordState::Paused)
+			}
+			"stop" => self.set_state(RecordState::Stopped),
+			"cancel" => self.set_state(RecordState::Cancelled),
+			"start" | "resume" | "pause" | "status" => {}
+			cmd => return format!("error: unknown command \"{}\"", cmd),
+		}
+		self.get_status()
+	}
+
+	/**
+	 * Start listening for the signals and socket commands.
+	 *
+	 * @param  control
+	 * @return Result
+	 */
+	pub fn start(control: &Arc<Self>) -> io::Result<()> {
+		Self::set_signal_handlers();
+		if let Some(path) = &control.socket {
+			Self::listen(control.clone(), path)?;
+		}
+		Ok(())
+	}
+
+	/* Update the state using the last received signal. */
+	#[cfg(unix)]
+	pub fn handle_signals(&self) {
+		let signal = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+		if signal == Signal::SIGUSR1 as i32 {
+			debug!("Received SIGUSR1.");
+			self.handle_command(match self.get_state() {
+				RecordState::Recording => "pause",
+				_ => "start",
+			});
+		} else if signal == Signal::SIGUSR2 as i32 {
+			debug!("Received SIGUSR2.");
+			self.handle_command("stop");
+		}
+	}
+
+	/* Signals are not supported on this platform. */
+	#[cfg(not(unix))]
+	pub fn handle_signals(&self) {}
+
+	/* Set the handlers for SIGUSR1 (start/pause) and SIGUSR2 (stop). */
+	#[cfg(unix)]
+	fn set_signal_handlers() {
+		let action = SigAction::new(
+			SigHandler::Handler(handle_signal),
+			SaFlags::SA_RESTART,
+			SigSet::empty(),
+		);
+		for signal in &[Signal::SIGUSR1, Signal::SIGUSR2] {
+			if unsafe { signal::sigaction(*signal, &action) }.is_err() {
+				warn!("Failed to set the signal handler for {}", signal);
+			}
+		}
+	}
+
+	/* Signals are not supported on this platform. */
+	#[cfg(not(unix))]
+	fn set_signal_handlers() {}
+
+	/**
+	 * Listen for the commands on a Unix domain socket.
+	 *
+	 * @param  control
+	 * @param  path
+	 * @return Result
+	 */
+	#[cfg(unix)]
+	fn listen(control: Arc<Self>, path: &Path) -> io::Result<()> {
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+		let listener = UnixListener::bind(path)?;
+		listener.set_nonblocking(true)?;
+		info!("Listening for commands on {:?}", path);
+		let path = path.to_path_buf();
+		thread::spawn(move || {
+			while !control.get_state().is_finished() {
+				match listener.accept() {
+					Ok((stream, _)) => {
+						if let Err(e) = control.handle_stream(stream) {
+							warn!("Failed to handle the command: {}", e);
+						}
+					}
+					Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+						thread::sleep(Duration::from_millis(SOCKET_POLL_INTERVAL));
+					}
+					Err(e) => {
+						warn!("Failed to accept the connection: {}", e);
+						break;
+					}
+				}
+			}
+			fs::remove_file(path).unwrap_or_default();
+		});
+		Ok(())
+	}
+
+	/* Sockets are not supported on this platform. */
+	#[cfg(not(unix))]
+	fn listen(_control: Arc<Self>, _path: &Path) -> io::Result<()> {
+		Err(io::Error::new(
+			ErrorKind::Other,
+			"Unix domain sockets are not supported",
+		))
+	}
+
+	/**
+	 * Read the commands from a stream and write the responses.
+	 *
+	 * @param  stream
+	 * @return Result
+	 */
+	#[cfg(unix)]
+	fn handle_stream(&self, mut stream: UnixStream) -> io::Result<()> {
+		stream.set_nonblocking(false)?;
+		stream
+			.set_read_timeout(Some(Duration::from_millis(SOCKET_POLL_INTERVAL)))?;
+		let mut reader = BufReader::new(stream.try_clone()?);
+		let mut line = String::new();
+		loop {
+			match reader.read_line(&mut line) {
+				Ok(0) => break,
+				Ok(_) => {
+					writeln!(stream, "{}", self.handle_command(&line))?;
+					line.clear();
+				}
+				Err(ref e)
+					if e.kind() == ErrorKind::WouldBlock
+						|| e.kind() == ErrorKind::TimedOut =>
+				{
+					if self.get_state().is_finished() {
+						break;
+					}
+				}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+}
+
+/**
+ * Save the received signal for handling it later.
+ *
+ * @param signal
+ */
+#[cfg(unix)]
+extern "C" fn handle_signal(signal: c_int) {
+	PENDING_SIGNAL.store(signal, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use std::env;
+	#[test]
+	fn test_record_control() -> io::Result<()> {
+		let control = RecordControl::new(true, None);
+		assert_eq!(RecordState::Waiting, control.get_state());
+		assert_eq!("recording 0", control.handle_command("start\n"));
+		control.set_frames(5);
+		assert_eq!("paused 5", control.handle_command("pause"));
+		assert_eq!("paused 5", control.handle_command("pause"));
+		assert_eq!("recording 5", control.handle_command("resume"));
+		assert!(control.handle_command("x").starts_with("error"));
+		RecordControl::set_signal_handlers();
+		signal::raise(Signal::SIGUSR1).unwrap();
+		control.handle_signals();
+		assert_eq!(RecordState::Paused, control.get_state());
+		signal::raise(Signal::SIGUSR2).unwrap();
+		control.handle_signals();
+		assert_eq!("stopped 5", control.get_status());
+		assert_eq!("stopped 5", control.handle_command("start"));
+		let path =
+			env::temp_dir().join(format!("{}-test.sock", env!("CARGO_PKG_NAME")));
+		let control = Arc::new(RecordControl::new(false, Some(path.clone())));
+		RecordControl::start(&control)?;
+		let mut stream = UnixStream::connect(&path)?;
+		writeln!(stream, "pause\nstatus\ncancel")?;
+		let mut lines = BufReader::new(stream).lines();
+		assert_eq!("paused 0", lines.next().unwrap()?);
+		assert_eq!("paused 0", lines.next().unwrap()?);
+		assert_eq!("cancelled 0", lines.next().unwrap()?);
+		assert_eq!(RecordState::Cancelled, control.get_state());
+		Ok(())
+	}
+}
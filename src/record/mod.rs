@@ -1,7 +1,9 @@
+pub mod control;
 pub mod fps;
 pub mod settings;
 
 use crate::image::Image;
+use crate::record::control::{RecordControl, RecordState};
 use crate::record::fps::FpsClock;
 use crate::record::settings::RecordSettings;
 use crate::util::state::InputState;
@@ -49,6 +51,7 @@ pub struct Recorder<Window> {
 	window: Window,
 	clock: FpsClock,
 	channel: (mpsc::Sender<()>, mpsc::Receiver<()>),
+	control: Arc<RecordControl>,
 	gifski: bool,
 	settings: RecordSettings,
 }
@@ -76,6 +79,7 @@ where
 			window,
 			clock: FpsClock::new(fps),
 			channel: mpsc::channel(),
+			control: Arc::new(settings.get_control()),
 			gifski,
 			settings,
 		}
@@ -104,6 +108,37 @@ where
 		}
 	}
 
+	/* Start listening for the external control commands. */
+	fn start_control(&self) {
+		if let Err(e) = RecordControl::start(&self.control) {
+			warn!("Failed to start the recording control: {}", e);
+		}
+		if self.control.get_state() == RecordState::Waiting {
+			info!("Waiting for the start command...");
+		}
+	}
+
+	/**
+	 * Check the recording state and return if a frame should be captured.
+	 *
+	 * @param  frames
+	 * @return bool (Option)
+	 */
+	fn check_state(&self, frames: &mut Vec<Image>) -> Option<bool> {
+		self.control.handle_signals();
+		match self.control.get_state() {
+			RecordState::Recording => Some(true),
+			RecordState::Waiting | RecordState::Paused => Some(false),
+			RecordState::Stopped => None,
+			RecordState::Cancelled => {
+				frames.clear();
+				debug!("\n");
+				warn!("Recording cancelled.");
+				None
+			}
+		}
+	}
+
 	/**
 	 * Record frames synchronously with blocking the current thread.
 	 *
@@ -118,6 +153,7 @@ where
 			rec_state.store(false, Ordering::SeqCst);
 		})
 		.expect("Failed to set the signal handler");
+		self.start_control();
 		self.window.show_countdown();
 		let max_frames = self.get_max_frames();
 		while recording.load(Ordering::SeqCst) && frames.len() < max_frames {
@@ -131,11 +167,21 @@ where
 					break;
 				}
 			}
+			let capture = match self.check_state(&mut frames) {
+				Some(capture) => capture,
+				None => break,
+			};
 			self.clock.tick();
-			frames.push(self.window.get_image().expect("Failed to get the image"));
-			debug!("Frames: {}\r", frames.len());
-			io::stdout().flush().expect("Failed to flush stdout");
+			if capture {
+				frames.push(self.settings.scale.apply(
+					self.window.get_image().expect("Failed to get the image"),
+				));
+				self.control.set_frames(frames.len());
+				debug!("Frames: {}\r", frames.len());
+				io::stdout().flush().expect("Failed to flush stdout");
+			}
 		}
+		self.control.set_state(RecordState::Stopped);
 		debug!("\n");
 		frames
 	}
@@ -150,20 +196,32 @@ where
 		RecordResult::new(
 			self.channel.0.clone(),
 			thread::spawn(move || {
+				self.start_control();
 				self.window.show_countdown();
 				let max_frames = self.get_max_frames();
 				while self.channel.1.try_recv().is_err() {
+					let capture = match self.check_state(&mut frames) {
+						Some(capture) => capture,
+						None => {
+							self.channel.1.recv().unwrap_or_default();
+							break;
+						}
+					};
 					self.clock.tick();
-					if frames.len() < max_frames {
+					if capture && frames.len() < max_frames {
 						frames.push(
-							self.window
-								.get_image()
-								.expect("Failed to get the image"),
+							self.settings.scale.apply(
+								self.window
+									.get_image()
+									.expect("Failed to get the image"),
+							),
 						);
+						self.control.set_frames(frames.len());
 						debug!("Frames: {}\r", frames.len());
 						io::stdout().flush().expect("Failed to flush stdout");
 					}
 				}
+				self.control.set_state(RecordState::Stopped);
 				debug!("\n");
 				frames
 			}),
@@ -174,9 +232,12 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::image::geometry::Geometry;
 	use crate::record::settings::RecordSettings;
+	use crate::window::pattern::{Pattern, PatternWindow};
 	use crate::window::test::TestWindow;
-	use pretty_assertions::assert_ne;
+	use image::ExtendedColorType;
+	use pretty_assertions::{assert_eq, assert_ne};
 	use std::thread;
 	use std::time::Duration;
 	#[test]
@@ -190,5 +251,19 @@ mod tests {
 			Recorder::new(window, 10, false, RecordSettings::default());
 		recorder.settings.time.duration = Some(0.2);
 		assert_ne!(0, recorder.record_sync(None).len());
+		let geometry = Geometry::new(0, 0, 64, 48);
+		let window = PatternWindow::new(Pattern::Counter, geometry);
+		let record = Recorder::new(window, 50, false, RecordSettings::default())
+			.record_async();
+		thread::sleep(Duration::from_millis(200));
+		let frames = record.get().unwrap().unwrap();
+		assert_ne!(0, frames.len());
+		assert_eq!(geometry, frames[0].geometry);
+		assert_eq!(
+			window
+				.get_frame(frames.len() as u32 - 1)
+				.get_data(ExtendedColorType::Rgb8),
+			frames[frames.len() - 1].get_data(ExtendedColorType::Rgb8)
+		);
 	}
 }
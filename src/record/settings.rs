@@ -1,8 +1,15 @@
 use crate::args::matches::ArgMatches;
 use crate::args::parser::ArgParser;
+use crate::edit::settings::ImageSettings;
 use crate::image::geometry::Geometry;
 use crate::image::padding::Padding;
+use crate::image::Image;
+use crate::record::control::RecordControl;
 use crate::util::command::Command;
+use crate::window::pattern::Pattern;
+use image::imageops::FilterType;
+use std::fmt;
+use std::path::PathBuf;
 
 /* Time related recording settings */
 #[derive(Clone, Copy, Debug)]
@@ -74,9 +81,12 @@ pub struct RecordFlag {
 	pub alpha: bool,
 	pub keys: Option<&'static str>,
 	pub font: Option<&'static str>,
-	pub monitor: Option<usize>,
+	pub monitor: Option<&'static str>,
 	pub select: bool,
 	pub mouse: bool,
+	pub drag: bool,
+	pub loupe: bool,
+	pub wait: bool,
 }
 
 /* Default initialization values for RecordFlag */
@@ -89,6 +99,9 @@ impl Default for RecordFlag {
 			monitor: None,
 			select: true,
 			mouse: false,
+			drag: false,
+			loupe: false,
+			wait: false,
 		}
 	}
 }
@@ -103,15 +116,22 @@ impl RecordFlag {
 	 * @param  monitor (Option)
 	 * @param  select
 	 * @param  mouse
+	 * @param  drag
+	 * @param  loupe
+	 * @param  wait
 	 * @return RecordFlag
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		alpha: bool,
 		keys: Option<&'static str>,
 		font: &str,
-		monitor: Option<usize>,
+		monitor: Option<&'static str>,
 		select: bool,
 		mouse: bool,
+		drag: bool,
+		loupe: bool,
+		wait: bool,
 	) -> Self {
 		Self {
 			alpha,
@@ -124,6 +144,121 @@ impl RecordFlag {
 			monitor,
 			select,
 			mouse,
+			drag,
+			loupe,
+			wait,
+		}
+	}
+}
+
+/* Scaling settings that are applied to the captured images */
+#[derive(Clone, Copy, Debug)]
+pub struct RecordScale {
+	pub ratio: f32,
+	pub max_width: Option<u32>,
+	pub max_height: Option<u32>,
+	pub filter: FilterType,
+}
+
+/* Default initialization values for RecordScale */
+impl Default for RecordScale {
+	fn default() -> Self {
+		Self {
+			ratio: 1.,
+			max_width: None,
+			max_height: None,
+			filter: FilterType::Lanczos3,
+		}
+	}
+}
+
+impl RecordScale {
+	/**
+	 * Create a new RecordScale object.
+	 *
+	 * @param  ratio
+	 * @param  max_width (Option)
+	 * @param  max_height (Option)
+	 * @param  filter
+	 * @return RecordScale
+	 */
+	pub fn new(
+		ratio: f32,
+		max_width: Option<u32>,
+		max_height: Option<u32>,
+		filter: FilterType,
+	) -> Self {
+		Self {
+			ratio,
+			max_width,
+			max_height,
+			filter,
+		}
+	}
+
+	/**
+	 * Create a RecordScale object from an argument parser.
+	 *
+	 * @param  parser
+	 * @return RecordScale
+	 */
+	fn from_parser(parser: &ArgParser<'_>) -> Self {
+		Self::new(
+			match parser.parse("scale", Self::default().ratio) {
+				ratio if ratio > 0. => ratio,
+				_ => Self::default().ratio,
+			},
+			match parser.parse("max-width", 0) {
+				width if width > 0 => Some(width),
+				_ => None,
+			},
+			match parser.parse("max-height", 0) {
+				height if height > 0 => Some(height),
+				_ => None,
+			},
+			ImageSettings::parse_filter(
+				parser
+					.args
+					.as_ref()
+					.and_then(|matches| matches.value_of("filter")),
+			),
+		)
+	}
+
+	/**
+	 * Get the scaled size while keeping the aspect ratio.
+	 *
+	 * @param  width
+	 * @param  height
+	 * @return Tuple (u32, u32)
+	 */
+	pub fn get_size(&self, width: u32, height: u32) -> (u32, u32) {
+		let mut ratio = f64::from(self.ratio);
+		if let Some(max_width) = self.max_width {
+			ratio = ratio.min(f64::from(max_width) / f64::from(width.max(1)));
+		}
+		if let Some(max_height) = self.max_height {
+			ratio = ratio.min(f64::from(max_height) / f64::from(height.max(1)));
+		}
+		(
+			((f64::from(width) * ratio).round() as u32).max(1),
+			((f64::from(height) * ratio).round() as u32).max(1),
+		)
+	}
+
+	/**
+	 * Scale the given image.
+	 *
+	 * @param  image
+	 * @return Image
+	 */
+	pub fn apply(&self, image: Image) -> Image {
+		let (width, height) =
+			self.get_size(image.geometry.width, image.geometry.height);
+		if (width, height) == (image.geometry.width, image.geometry.height) {
+			image
+		} else {
+			image.resize(width, height, self.filter)
 		}
 	}
 }
@@ -143,6 +278,11 @@ impl RecordWindow {
 	 * @return RecordWindow
 	 */
 	fn from_args(matches: &ArgMatches<'_>) -> Self {
+		if matches.is_present("headless") {
+			return Self::Root(None);
+		} else if matches.is_present("drag") {
+			return Self::Root(Some(Geometry::default()));
+		}
 		let size =
 			if matches.occurrences_of("size") != 0 || matches.is_present("select") {
 				Some(Geometry::parse(
@@ -165,13 +305,90 @@ impl RecordWindow {
 	}
 }
 
+/* Private X server to record in (headless mode) */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordServer {
+	Xvfb(Geometry),
+	Xephyr(Geometry),
+}
+
+impl RecordServer {
+	/**
+	 * Create a RecordServer enum from parsed arguments.
+	 *
+	 * @param  matches
+	 * @return RecordServer (Option)
+	 */
+	fn from_args(matches: &ArgMatches<'_>) -> Option<Self> {
+		let geometry = Geometry::parse(matches.value_of("headless")?);
+		match matches.value_of("server") {
+			Some("xephyr") => Some(Self::Xephyr(geometry)),
+			_ => Some(Self::Xvfb(geometry)),
+		}
+	}
+}
+
+/* VNC server to record instead of the local window system */
+#[derive(Clone, Copy)]
+pub struct RecordVnc {
+	pub address: &'static str,
+	pub password: Option<&'static str>,
+}
+
+/* Debug implementation for hiding the password */
+impl fmt::Debug for RecordVnc {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RecordVnc")
+			.field("address", &self.address)
+			.field("password", &self.password.map(|_| "***"))
+			.finish()
+	}
+}
+
+impl RecordVnc {
+	/**
+	 * Create a new RecordVnc object.
+	 *
+	 * @param  address
+	 * @param  password (Option)
+	 * @return RecordVnc
+	 */
+	pub fn new(address: &'static str, password: Option<&'static str>) -> Self {
+		Self { address, password }
+	}
+
+	/**
+	 * Create a RecordVnc object from parsed arguments.
+	 *
+	 * @param  matches
+	 * @return RecordVnc (Option)
+	 */
+	fn from_args(matches: &ArgMatches<'_>) -> Option<Self> {
+		Some(Self::new(
+			Box::leak(matches.value_of("vnc")?.to_string().into_boxed_str()),
+			match matches.value_of("vnc-password") {
+				Some(password) => {
+					Some(Box::leak(password.to_string().into_boxed_str()))
+				}
+				_ => None,
+			},
+		))
+	}
+}
+
 /* Recording and window settings */
 #[derive(Clone, Copy, Debug)]
 pub struct RecordSettings {
 	pub command: Option<&'static str>,
+	pub socket: Option<&'static str>,
+	pub server: Option<RecordServer>,
+	pub vnc: Option<RecordVnc>,
+	pub pattern: Option<Pattern>,
 	pub color: u64,
+	pub background: u64,
 	pub border: Option<u32>,
 	pub padding: Padding,
+	pub scale: RecordScale,
 	pub time: RecordTime,
 	pub flag: RecordFlag,
 	pub window: RecordWindow,
@@ -182,9 +399,15 @@ impl Default for RecordSettings {
 	fn default() -> Self {
 		Self {
 			command: None,
+			socket: None,
+			server: None,
+			vnc: None,
+			pattern: None,
 			color: 0x003A_A431,
+			background: 0x0000_0000,
 			border: Some(1),
 			padding: Padding::default(),
+			scale: RecordScale::default(),
 			time: RecordTime::default(),
 			flag: RecordFlag::default(),
 			window: RecordWindow::Focus(Some(Geometry::default()), false),
@@ -197,28 +420,47 @@ impl RecordSettings {
 	 * Create a new RecordSettings object.
 	 *
 	 * @param  command (Option)
+	 * @param  socket (Option)
+	 * @param  server (Option)
+	 * @param  vnc (Option)
+	 * @param  pattern (Option)
 	 * @param  color
+	 * @param  background
 	 * @param  border (Option)
 	 * @param  padding
+	 * @param  scale
 	 * @param  time
 	 * @param  flag
 	 * @param  window
 	 * @return RecordSettings
 	 */
+	#[allow(clippy::too_many_arguments)]
 	pub fn new(
 		command: Option<&'static str>,
+		socket: Option<&'static str>,
+		server: Option<RecordServer>,
+		vnc: Option<RecordVnc>,
+		pattern: Option<Pattern>,
 		color: u64,
+		background: u64,
 		border: Option<u32>,
 		padding: Padding,
+		scale: RecordScale,
 		time: RecordTime,
 		flag: RecordFlag,
 		window: RecordWindow,
 	) -> Self {
 		Self {
 			command,
+			socket,
+			server,
+			vnc,
+			pattern,
 			color,
+			background,
 			border,
 			padding,
+			scale,
 			time,
 			flag,
 			window,
@@ -259,12 +501,25 @@ impl RecordSettings {
 					Some(cmd) => Some(Box::leak(cmd.to_string().into_boxed_str())),
 					_ => None,
 				},
+				match matches.value_of("socket") {
+					Some(path) => Some(Box::leak(path.to_string().into_boxed_str())),
+					_ => None,
+				},
+				RecordServer::from_args(&matches),
+				RecordVnc::from_args(&matches),
+				matches.value_of("pattern").and_then(|v| v.parse().ok()),
 				u64::from_str_radix(color, 16).unwrap_or(Self::default().color),
+				u64::from_str_radix(
+					matches.value_of("background").unwrap_or_default(),
+					16,
+				)
+				.unwrap_or(Self::default().background),
 				match parser.parse("border", 0) {
 					border if border > 0 => Some(border),
 					_ => None,
 				},
 				Self::parse_padding(&matches),
+				RecordScale::from_parser(&parser),
 				RecordTime::from_parser(&parser),
 				RecordFlag::new(
 					matches.is_present("with-alpha"),
@@ -280,13 +535,21 @@ impl RecordSettings {
 						))
 					},
 					matches.value_of("font").unwrap_or_default(),
-					matches.value_of("monitor").and_then(|v| v.parse().ok()),
+					match matches.value_of("monitor") {
+						Some(monitor) => {
+							Some(Box::leak(monitor.to_string().into_boxed_str()))
+						}
+						_ => None,
+					},
 					if matches.value_of("size").unwrap_or_default().contains('+') {
 						matches.is_present("select")
 					} else {
 						true
 					},
 					matches.is_present("mouse"),
+					matches.is_present("drag"),
+					matches.is_present("loupe"),
+					matches.is_present("wait"),
 				),
 				RecordWindow::from_args(&matches),
 			),
@@ -341,6 +604,15 @@ impl RecordSettings {
 			_ => None,
 		}
 	}
+
+	/**
+	 * Get RecordControl from parsed settings.
+	 *
+	 * @return RecordControl
+	 */
+	pub fn get_control(&self) -> RecordControl {
+		RecordControl::new(self.flag.wait, self.socket.map(PathBuf::from))
+	}
 }
 
 #[cfg(test)]
@@ -375,6 +647,34 @@ mod tests {
 			.arg(Arg::with_name("focus").long("focus"))
 			.arg(Arg::with_name("with-alpha").long("with-alpha"))
 			.arg(Arg::with_name("no-keys").long("no-keys"))
+			.arg(Arg::with_name("socket").long("socket").takes_value(true))
+			.arg(Arg::with_name("wait").long("wait"))
+			.arg(
+				Arg::with_name("headless")
+					.long("headless")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("server").long("server").takes_value(true))
+			.arg(Arg::with_name("vnc").long("vnc").takes_value(true))
+			.arg(Arg::with_name("pattern").long("pattern").takes_value(true))
+			.arg(Arg::with_name("monitor").long("monitor").takes_value(true))
+			.arg(
+				Arg::with_name("background")
+					.long("background")
+					.takes_value(true),
+			)
+			.arg(
+				Arg::with_name("vnc-password")
+					.long("vnc-password")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("scale").long("scale").takes_value(true))
+			.arg(
+				Arg::with_name("max-width")
+					.long("max-width")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("filter").long("filter").takes_value(true))
 			.get_matches_from(vec![
 				"test",
 				"--keys",
@@ -395,6 +695,25 @@ mod tests {
 				"12",
 				"--root",
 				"--with-alpha",
+				"--socket",
+				"/tmp/test.sock",
+				"--wait",
+				"--vnc",
+				"localhost:1",
+				"--vnc-password",
+				"secret",
+				"--pattern",
+				"boxes",
+				"--monitor",
+				"DP-1,primary",
+				"--background",
+				"1C1C1C",
+				"--scale",
+				"0.5",
+				"--max-width",
+				"1280",
+				"--filter",
+				"nearest",
 			]);
 		let record_settings =
 			RecordSettings::from_parser(ArgParser::from_args(&args), "000000");
@@ -409,6 +728,58 @@ mod tests {
 			record_settings.window
 		);
 		assert!(record_settings.flag.alpha);
+		assert!(record_settings.flag.wait);
+		assert_eq!(Some("/tmp/test.sock"), record_settings.socket);
 		assert_eq!("LControl-Q/S", record_settings.flag.keys.unwrap());
+		assert_eq!(None, record_settings.server);
+		let vnc = record_settings.vnc.unwrap();
+		assert_eq!(("localhost:1", Some("secret")), (vnc.address, vnc.password));
+		assert!(!format!("{:?}", vnc).contains("secret"));
+		assert_eq!(Some(Pattern::Boxes), record_settings.pattern);
+		assert_eq!(Some("DP-1,primary"), record_settings.flag.monitor);
+		assert_eq!(0x001C_1C1C, record_settings.background);
+		assert_eq!(FilterType::Nearest, record_settings.scale.filter);
+		assert_eq!((1280, 720), record_settings.scale.get_size(3840, 2160));
+		assert_eq!((960, 540), record_settings.scale.get_size(1920, 1080));
+		assert_eq!((1, 1), record_settings.scale.get_size(1, 1));
+		let image = record_settings.scale.apply(Image::new(
+			vec![image::Bgra::from([0, 0, 0, 0]); 16],
+			false,
+			Geometry::new(0, 0, 4, 4),
+		));
+		assert_eq!(Geometry::new(0, 0, 2, 2), image.geometry);
+		assert_eq!((64, 48), RecordScale::default().get_size(64, 48));
+		let args = App::new("test")
+			.arg(
+				Arg::with_name("headless")
+					.long("headless")
+					.takes_value(true),
+			)
+			.arg(Arg::with_name("server").long("server").takes_value(true))
+			.get_matches_from(vec![
+				"test",
+				"--headless",
+				"640x480",
+				"--server",
+				"xephyr",
+			]);
+		let record_settings =
+			RecordSettings::from_parser(ArgParser::from_args(&args), "000000");
+		assert_eq!(
+			Some(RecordServer::Xephyr(Geometry::new(0, 0, 640, 480))),
+			record_settings.server
+		);
+		assert_eq!(RecordWindow::Root(None), record_settings.window);
+		let args = App::new("test")
+			.arg(Arg::with_name("drag").long("drag"))
+			.arg(Arg::with_name("loupe").long("loupe"))
+			.get_matches_from(vec!["test", "--drag", "--loupe"]);
+		let record_settings =
+			RecordSettings::from_parser(ArgParser::from_args(&args), "000000");
+		assert!(record_settings.flag.drag && record_settings.flag.loupe);
+		assert_eq!(
+			RecordWindow::Root(Some(Geometry::default())),
+			record_settings.window
+		);
 	}
 }
@@ -1,12 +1,18 @@
 use crate::analyze::settings::AnalyzeSettings;
 use crate::anim::settings::{AnimSettings, SplitSettings};
 use crate::args::matches::ArgMatches;
+use crate::daemon::settings::DaemonSettings;
 use crate::edit::settings::EditSettings;
 use crate::file::format::FileFormat;
 use crate::file::settings::SaveSettings;
 use crate::image::geometry::Geometry;
 use crate::image::settings::{JpgSettings, PngSettings, PnmSettings};
+use crate::measure::settings::MeasureSettings;
+use crate::montage::settings::MontageSettings;
+use crate::optimize::settings::OptimizeSettings;
+use crate::pick::settings::PickSettings;
 use crate::record::settings::{RecordSettings, RecordWindow};
+use crate::util::event::InputEvents;
 use crate::util::keys::ActionKeys;
 use crate::util::state::InputState;
 use colored::Color;
@@ -23,6 +29,11 @@ pub struct AppSettings<'a> {
 	pub pnm: PnmSettings,
 	pub edit: EditSettings,
 	pub analyze: AnalyzeSettings,
+	pub optimize: OptimizeSettings,
+	pub pick: PickSettings,
+	pub measure: MeasureSettings,
+	pub montage: MontageSettings,
+	pub daemon: DaemonSettings,
 	pub save: SaveSettings,
 	pub input_state: Option<&'static InputState>,
 	pub window_required: bool,
@@ -42,15 +53,28 @@ impl<'a> AppSettings<'a> {
 		let pnm = PnmSettings::from_args(args);
 		let edit = EditSettings::from_args(args);
 		let save = SaveSettings::from_args(args, &edit, &pnm);
-		let input_state = Self::get_input_state(window_required, &record);
+		let input_state = Self::get_input_state(
+			(window_required
+				&& record.server.is_none()
+				&& ((record.vnc.is_none() && record.pattern.is_none())
+					|| record.flag.keys.is_some()))
+				|| args.is_present("pick")
+				|| args.is_present("measure"),
+			&record,
+		);
 		Self {
 			args,
 			record,
 			anim: AnimSettings::from_args(args, &save.file.format),
 			split: SplitSettings::from_args(args),
-			png: PngSettings::from_args(args),
+			png: PngSettings::from_args(args, &save.file.format),
 			jpg: JpgSettings::from_args(args),
 			analyze: AnalyzeSettings::from_args(args, Self::get_color(args)),
+			optimize: OptimizeSettings::from_args(args),
+			pick: PickSettings::from_args(args),
+			measure: MeasureSettings::from_args(args),
+			montage: MontageSettings::from_args(args),
+			daemon: DaemonSettings::from_args(args),
 			pnm,
 			edit,
 			save,
@@ -103,12 +127,12 @@ impl<'a> AppSettings<'a> {
 		if window_required {
 			Some(Box::leak(
 				InputState::new(
-					if let Some(keys) = record.flag.keys {
-						ActionKeys::parse(keys)
-					} else {
-						ActionKeys::default()
+					match record.flag.keys {
+						Some(keys) if !keys.is_empty() => ActionKeys::parse(keys),
+						_ => ActionKeys::default(),
 					},
 					record.flag.mouse,
+					Self::get_input_events(),
 				)
 				.into_boxed_state(),
 			))
@@ -117,6 +141,18 @@ impl<'a> AppSettings<'a> {
 		}
 	}
 
+	/**
+	 * Get the input events from the window system (if supported).
+	 *
+	 * @return InputEvents (Option)
+	 */
+	fn get_input_events() -> Option<&'static InputEvents> {
+		#[cfg(all(unix, not(target_os = "macos")))]
+		return crate::x11::input::XInput::start();
+		#[cfg(not(all(unix, not(target_os = "macos"))))]
+		None
+	}
+
 	/* Check the settings and update if necessary. */
 	pub fn check(&mut self) {
 		trace!("{:?}", self);
@@ -130,8 +166,10 @@ impl<'a> AppSettings<'a> {
 			self.record.time.countdown = 99;
 			warn!("Countdown value cannot be greater than 99.")
 		}
-		if let Some(input_state) = self.input_state {
-			if self.record.flag.keys != Some(&ActionKeys::default().to_string()) {
+		if let (Some(input_state), Some(keys)) =
+			(self.input_state, self.record.flag.keys)
+		{
+			if !keys.is_empty() && keys != ActionKeys::default().to_string() {
 				info!(
 					"Using custom action keys: {}",
 					input_state.action_keys.to_string()
@@ -0,0 +1,166 @@
+use device_query::{Keycode, MouseState};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/* Maximum number of events to keep in the queue */
+const MAX_EVENTS: usize = 1024;
+
+/* Kind of the input event */
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEventKind {
+	KeyPress(Keycode),
+	KeyRelease(Keycode),
+	ButtonPress(usize),
+	ButtonRelease(usize),
+	Motion(i32, i32),
+}
+
+/* Timestamped input event along with the pressed keys */
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputEvent {
+	pub time: u64,
+	pub kind: InputEventKind,
+	pub keys: Vec<Keycode>,
+}
+
+/* State of the inputs that is updated by the events */
+#[derive(Debug, Default)]
+struct EventState {
+	keys: Vec<(u8, Keycode)>,
+	buttons: [bool; 6],
+	pointer: (i32, i32),
+	queue: VecDeque<InputEvent>,
+	count: usize,
+}
+
+/* Input events that are shared between threads */
+#[derive(Debug, Default)]
+pub struct InputEvents {
+	state: Mutex<EventState>,
+}
+
+impl InputEvents {
+	/**
+	 * Update the state and push a new event to the queue.
+	 *
+	 * @param time
+	 * @param kind
+	 * @param code (Option)
+	 */
+	pub fn push(&self, time: u64, kind: InputEventKind, code: Option<u8>) {
+		let mut state = self.state.lock().expect("Failed to lock the input state");
+		match (&kind, code) {
+			(InputEventKind::KeyPress(key), Some(code)) => {
+				if let Err(i) = state.keys.binary_search_by_key(&code, |(c, _)| *c) {
+					state.keys.insert(i, (code, key.clone()));
+				} else {
+					return;
+				}
+			}
+			(InputEventKind::KeyRelease(_), Some(code)) => {
+				state.keys.retain(|(c, _)| *c != code)
+			}
+			(InputEventKind::ButtonPress(button), _) => {
+				if let Some(pressed) = state.buttons.get_mut(*button) {
+					*pressed = true;
+				}
+			}
+			(InputEventKind::ButtonRelease(button), _) => {
+				if let Some(pressed) = state.buttons.get_mut(*button) {
+					*pressed = false;
+				}
+			}
+			(InputEventKind::Motion(x, y), _) => state.pointer = (*x, *y),
+			_ => {}
+		}
+		let keys = state.keys.iter().map(|(_, key)| key.clone()).collect();
+		if state.queue.len() == MAX_EVENTS {
+			state.queue.pop_front();
+		}
+		state.queue.push_back(InputEvent { time, kind, keys });
+		state.count += 1;
+	}
+
+	/**
+	 * Get the keys that are currently pressed.
+	 *
+	 * @return Vector of Keycode
+	 */
+	pub fn get_keys(&self) -> Vec<Keycode> {
+		let state = self.state.lock().expect("Failed to lock the input state");
+		state.keys.iter().map(|(_, key)| key.clone()).collect()
+	}
+
+	/**
+	 * Get the current state of the mouse.
+	 *
+	 * @return MouseState
+	 */
+	pub fn get_mouse(&self) -> MouseState {
+		let state = self.state.lock().expect("Failed to lock the input state");
+		MouseState {
+			coords: state.pointer,
+			button_pressed: state.buttons.to_vec(),
+		}
+	}
+
+	/**
+	 * Get the events that are received after the given event number.
+	 *
+	 * @param  since
+	 * @return Tuple (Vector of InputEvent, usize)
+	 */
+	pub fn get_events(&self, since: usize) -> (Vec<InputEvent>, usize) {
+		let state = self.state.lock().expect("Failed to lock the input state");
+		let first = state.count - state.queue.len();
+		(
+			state
+				.queue
+				.iter()
+				.skip(since.saturating_sub(first))
+				.cloned()
+				.collect(),
+			state.count,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_input_events() {
+		let events = InputEvents::default();
+		events.push(1, InputEventKind::KeyPress(Keycode::S), Some(39));
+		events.push(2, InputEventKind::KeyPress(Keycode::LAlt), Some(64));
+		events.push(3, InputEventKind::KeyPress(Keycode::S), Some(39));
+		events.push(4, InputEventKind::KeyRelease(Keycode::S), Some(39));
+		events.push(5, InputEventKind::ButtonPress(1), None);
+		events.push(6, InputEventKind::Motion(10, 20), None);
+		assert_eq!(vec![Keycode::LAlt], events.get_keys());
+		let mouse = events.get_mouse();
+		assert_eq!((10, 20), mouse.coords);
+		assert!(mouse.button_pressed[1]);
+		let (received, count) = events.get_events(0);
+		assert_eq!(5, count);
+		assert_eq!(
+			InputEvent {
+				time: 2,
+				kind: InputEventKind::KeyPress(Keycode::LAlt),
+				keys: vec![Keycode::S, Keycode::LAlt],
+			},
+			received[1]
+		);
+		assert_eq!(
+			vec![4, 5, 6],
+			events
+				.get_events(2)
+				.0
+				.iter()
+				.map(|event| event.time)
+				.collect::<Vec<u64>>()
+		);
+		assert!(events.get_events(count).0.is_empty());
+	}
+}
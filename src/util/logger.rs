@@ -31,6 +31,7 @@ impl<'a> Logger<'a> {
 				.trace(Color::BrightBlack),
 			level_filter: if settings.args.is_present("quiet")
 				|| settings.save.file.path.to_str() == Some("-")
+				|| settings.pick.json
 			{
 				LevelFilter::Off
 			} else {
@@ -1,4 +1,5 @@
 pub mod command;
+pub mod event;
 pub mod keys;
 pub mod logger;
 pub mod state;
@@ -1,18 +1,23 @@
+use crate::util::event::{InputEvent, InputEventKind, InputEvents};
 use crate::util::keys::ActionKeys;
-use device_query::{DeviceQuery, DeviceState, Keycode};
+use device_query::{DeviceQuery, DeviceState, Keycode, MouseState};
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /* State of the mouse and keyboard inputs */
 pub struct InputState {
 	pub state: DeviceState,
+	pub events: Option<&'static InputEvents>,
 	pub action_keys: ActionKeys,
 	pub check_mouse: bool,
+	action_event: AtomicUsize,
+	cancel_event: AtomicUsize,
 }
 
 /* Default initialization values for InputState */
 impl Default for InputState {
 	fn default() -> Self {
-		Self::new(ActionKeys::default(), false)
+		Self::new(ActionKeys::default(), false, None)
 	}
 }
 
@@ -20,8 +25,9 @@ impl Default for InputState {
 impl fmt::Debug for InputState {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("InputState")
-			.field("mouse", &self.state.get_mouse())
-			.field("keys", &self.state.get_keys())
+			.field("mouse", &self.get_mouse())
+			.field("keys", &self.get_keys())
+			.field("events", &self.events.is_some())
 			.finish()
 	}
 }
@@ -35,13 +41,21 @@ impl InputState {
 	 *
 	 * @param  action_keys
 	 * @param  check_mouse
+	 * @param  events (Option)
 	 * @return InputState
 	 */
-	pub fn new(action_keys: ActionKeys, check_mouse: bool) -> Self {
+	pub fn new(
+		action_keys: ActionKeys,
+		check_mouse: bool,
+		events: Option<&'static InputEvents>,
+	) -> Self {
 		Self {
 			state: DeviceState::new(),
+			events,
 			action_keys,
 			check_mouse,
+			action_event: AtomicUsize::new(0),
+			cancel_event: AtomicUsize::new(0),
 		}
 	}
 
@@ -54,12 +68,56 @@ impl InputState {
 		Box::new(self)
 	}
 
+	/**
+	 * Get the keys that are currently pressed.
+	 *
+	 * @return Vector of Keycode
+	 */
+	pub fn get_keys(&self) -> Vec<Keycode> {
+		match self.events {
+			Some(events) => events.get_keys(),
+			None => self.state.get_keys(),
+		}
+	}
+
+	/**
+	 * Get the current state of the mouse.
+	 *
+	 * @return MouseState
+	 */
+	pub fn get_mouse(&self) -> MouseState {
+		match self.events {
+			Some(events) => events.get_mouse(),
+			None => self.state.get_mouse(),
+		}
+	}
+
+	/**
+	 * Get the events that are received since the last check.
+	 *
+	 * @param  last_event
+	 * @return Vector of InputEvent (Option)
+	 */
+	fn get_new_events(&self, last_event: &AtomicUsize) -> Option<Vec<InputEvent>> {
+		let (events, count) =
+			self.events?.get_events(last_event.load(Ordering::SeqCst));
+		last_event.store(count, Ordering::SeqCst);
+		Some(events)
+	}
+
 	/**
 	 * Check for action keys and mouse state to see if there is any action.
 	 *
 	 * @return bool
 	 */
 	pub fn check_action(&self) -> bool {
+		if let Some(events) = self.get_new_events(&self.action_event) {
+			return events.into_iter().any(|event| match event.kind {
+				InputEventKind::KeyPress(_) => self.action_keys.check(event.keys),
+				InputEventKind::ButtonPress(1) => self.check_mouse,
+				_ => false,
+			});
+		}
 		let keys_pressed = self.action_keys.check(self.state.get_keys());
 		if self.check_mouse {
 			keys_pressed || self.state.get_mouse().button_pressed[1]
@@ -74,10 +132,16 @@ impl InputState {
 	 * @return bool
 	 */
 	pub fn check_cancel_keys(&self) -> bool {
-		matches!(
-			self.state.get_keys().as_slice(),
-			[Keycode::Escape] | [Keycode::LControl, Keycode::D]
-		)
+		let is_cancel = |keys: &[Keycode]| {
+			matches!(keys, [Keycode::Escape] | [Keycode::LControl, Keycode::D])
+		};
+		match self.get_new_events(&self.cancel_event) {
+			Some(events) => events.iter().any(|event| {
+				matches!(event.kind, InputEventKind::KeyPress(_))
+					&& is_cancel(&event.keys)
+			}),
+			None => is_cancel(&self.state.get_keys()),
+		}
 	}
 }
 
@@ -91,5 +155,21 @@ mod tests {
 		assert!(!input_state.check_action());
 		assert!(!input_state.check_cancel_keys());
 		assert!(format!("{:?}", input_state).len() > 0);
+		let events = Box::leak(Box::new(InputEvents::default()));
+		let input_state = InputState::new(ActionKeys::default(), true, Some(events));
+		events.push(0, InputEventKind::KeyPress(Keycode::LAlt), Some(64));
+		events.push(1, InputEventKind::KeyPress(Keycode::S), Some(39));
+		events.push(2, InputEventKind::KeyRelease(Keycode::S), Some(39));
+		assert_eq!(vec![Keycode::LAlt], input_state.get_keys());
+		assert!(input_state.check_action());
+		assert!(!input_state.check_action());
+		events.push(3, InputEventKind::ButtonPress(1), None);
+		assert!(input_state.check_action());
+		events.push(4, InputEventKind::KeyRelease(Keycode::LAlt), Some(64));
+		events.push(5, InputEventKind::KeyPress(Keycode::Escape), Some(9));
+		events.push(6, InputEventKind::KeyRelease(Keycode::Escape), Some(9));
+		assert!(input_state.check_cancel_keys());
+		assert!(!input_state.check_cancel_keys());
+		assert!(input_state.get_mouse().button_pressed[1]);
 	}
 }
@@ -0,0 +1,496 @@
+use crate::image::geometry::Geometry;
+use crate::vnc::des;
+use image::Bgra;
+use std::fmt;
+use std::io::{self, Error, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream};
+
+/* Default port of the VNC servers */
+const DEFAULT_PORT: u16 = 5900;
+/* Supported security types */
+const SECURITY_NONE: u8 = 1;
+const SECURITY_VNC_AUTH: u8 = 2;
+/* Supported encodings */
+const ENCODING_RAW: i32 = 0;
+const ENCODING_COPY_RECT: i32 = 1;
+const ENCODING_DESKTOP_SIZE: i32 = -223;
+/* Pixel format to request (32-bit little-endian BGRX) */
+const PIXEL_FORMAT: [u8; 16] =
+	[32, 24, 0, 1, 0, 255, 0, 255, 0, 255, 16, 8, 0, 0, 0, 0];
+
+/* Client for the Remote Framebuffer (RFB) protocol */
+pub struct VncClient {
+	stream: TcpStream,
+	pub name: String,
+	pub width: u16,
+	pub height: u16,
+	framebuffer: Vec<Bgra<u8>>,
+	requested: bool,
+	full_update: bool,
+}
+
+/* Debug implementation for programmer-facing output */
+impl fmt::Debug for VncClient {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("VncClient")
+			.field("peer", &self.stream.peer_addr().ok())
+			.field("name", &self.name)
+			.field("width", &self.width)
+			.field("height", &self.height)
+			.finish()
+	}
+}
+
+impl VncClient {
+	/**
+	 * Connect to a VNC server.
+	 *
+	 * @param  address
+	 * @param  password (Option)
+	 * @return VncClient (Result)
+	 */
+	pub fn connect(address: &str, password: Option<&str>) -> io::Result<Self> {
+		let stream = TcpStream::connect(Self::get_address(address))?;
+		stream.set_nodelay(true)?;
+		let mut client = Self {
+			stream,
+			name: String::new(),
+			width: 0,
+			height: 0,
+			framebuffer: Vec::new(),
+			requested: false,
+			full_update: true,
+		};
+		client.handshake(password)?;
+		client.init()?;
+		Ok(client)
+	}
+
+	/**
+	 * Get the socket address from the VNC address.
+	 *
+	 * Port numbers lower than 100 are treated as display
+	 * numbers (e.g. "localhost:1" is "localhost:5901").
+	 *
+	 * @param  address
+	 * @return String
+	 */
+	fn get_address(address: &str) -> String {
+		let mut values = address.rsplitn(2, ':');
+		match (values.next(), values.next()) {
+			(Some(port), Some(host)) => match port.parse::<u16>() {
+				Ok(display) if display < 100 => {
+					format!("{}:{}", host, DEFAULT_PORT + display)
+				}
+				_ => address.to_string(),
+			},
+			_ => format!("{}:{}", address, DEFAULT_PORT),
+		}
+	}
+
+	/**
+	 * Negotiate the protocol version and security type.
+	 *
+	 * @param  password (Option)
+	 * @return Result
+	 */
+	fn handshake(&mut self, password: Option<&str>) -> io::Result<()> {
+		let mut version = [0; 12];
+		self.stream.read_exact(&mut version)?;
+		let minor = match std::str::from_utf8(&version) {
+			Ok(v) if v.starts_with("RFB 003.") => {
+				v[8..11].parse::<u8>().unwrap_or_default()
+			}
+			_ => {
+				return Err(Error::new(
+					ErrorKind::InvalidData,
+					"Unsupported RFB protocol version",
+				))
+			}
+		};
+		let minor = match minor {
+			v if v >= 8 => 8,
+			7 => 7,
+			_ => 3,
+		};
+		debug!("RFB protocol version: 3.{}", minor);
+		self.stream
+			.write_all(format!("RFB 003.{:03}\n", minor).as_bytes())?;
+		let security = if minor >= 7 {
+			let mut types = vec![0; usize::from(self.read_u8()?)];
+			if types.is_empty() {
+				return Err(self.read_reason()?);
+			}
+			self.stream.read_exact(&mut types)?;
+			let security = if types.contains(&SECURITY_NONE)
+				&& (password.is_none() || !types.contains(&SECURITY_VNC_AUTH))
+			{
+				SECURITY_NONE
+			} else if types.contains(&SECURITY_VNC_AUTH) {
+				SECURITY_VNC_AUTH
+			} else {
+				return Err(Error::new(
+					ErrorKind::InvalidData,
+					format!("Unsupported security types: {:?}", types),
+				));
+			};
+			self.stream.write_all(&[security])?;
+			security
+		} else {
+			match self.read_u32()? {
+				0 => return Err(self.read_reason()?),
+				v if v == u32::from(SECURITY_NONE) => SECURITY_NONE,
+				v if v == u32::from(SECURITY_VNC_AUTH) => SECURITY_VNC_AUTH,
+				v => {
+					return Err(Error::new(
+						ErrorKind::InvalidData,
+						format!("Unsupported security type: {}", v),
+					))
+				}
+			}
+		};
+		if security == SECURITY_VNC_AUTH {
+			let password = password.ok_or_else(|| {
+				Error::new(ErrorKind::PermissionDenied, "VNC password required")
+			})?;
+			let mut challenge = [0; 16];
+			self.stream.read_exact(&mut challenge)?;
+			self.stream
+				.write_all(&des::get_vnc_response(password, &challenge))?;
+		}
+		if (security == SECURITY_VNC_AUTH || minor >= 8) && self.read_u32()? != 0 {
+			return Err(if minor >= 8 {
+				Error::new(ErrorKind::PermissionDenied, self.read_reason()?)
+			} else {
+				Error::new(ErrorKind::PermissionDenied, "Authentication failed")
+			});
+		}
+		Ok(())
+	}
+
+	/**
+	 * Initialize the session and set the pixel format and encodings.
+	 *
+	 * @return Result
+	 */
+	fn init(&mut self) -> io::Result<()> {
+		self.stream.write_all(&[1])?;
+		self.width = self.read_u16()?;
+		self.height = self.read_u16()?;
+		self.skip(PIXEL_FORMAT.len())?;
+		let mut name = vec![0; self.read_u32()? as usize];
+		self.stream.read_exact(&mut name)?;
+		self.name = String::from_utf8_lossy(&name).to_string();
+		self.resize(self.width, self.height);
+		info!(
+			"Connected to the VNC server: {:?} ({}x{})",
+			self.name, self.width, self.height
+		);
+		let mut message = vec![0, 0, 0, 0];
+		message.extend_from_slice(&PIXEL_FORMAT);
+		self.stream.write_all(&message)?;
+		let encodings = [ENCODING_COPY_RECT, ENCODING_RAW, ENCODING_DESKTOP_SIZE];
+		let mut message = vec![2, 0];
+		message.extend_from_slice(&(encodings.len() as u16).to_be_bytes());
+		for encoding in encodings.iter() {
+			message.extend_from_slice(&encoding.to_be_bytes());
+		}
+		self.stream.write_all(&message)
+	}
+
+	/**
+	 * Resize the framebuffer.
+	 *
+	 * @param width
+	 * @param height
+	 */
+	fn resize(&mut self, width: u16, height: u16) {
+		self.width = width;
+		self.height = height;
+		self.framebuffer = vec![
+			Bgra::from([0, 0, 0, 255]);
+			usize::from(width) * usize::from(height)
+		];
+		self.full_update = true;
+	}
+
+	/**
+	 * Request an update of the framebuffer.
+	 *
+	 * @return Result
+	 */
+	fn request_update(&mut self) -> io::Result<()> {
+		let mut message = vec![3, if self.full_update { 0 } else { 1 }, 0, 0, 0, 0];
+		message.extend_from_slice(&self.width.to_be_bytes());
+		message.extend_from_slice(&self.height.to_be_bytes());
+		self.stream.write_all(&message)?;
+		self.requested = true;
+		self.full_update = false;
+		Ok(())
+	}
+
+	/**
+	 * Check if there is a message waiting to be read.
+	 *
+	 * @return bool (Result)
+	 */
+	fn has_pending(&self) -> io::Result<bool> {
+		self.stream.set_nonblocking(true)?;
+		let pending = match self.stream.peek(&mut [0]) {
+			Ok(0) => Err(Error::new(
+				ErrorKind::UnexpectedEof,
+				"Connection closed by the VNC server",
+			)),
+			Ok(_) => Ok(true),
+			Err(ref e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+			Err(e) => Err(e),
+		};
+		self.stream.set_nonblocking(false)?;
+		pending
+	}
+
+	/**
+	 * Update the framebuffer with the pending messages.
+	 *
+	 * Waits for the first update and does not block afterwards
+	 * unless there is an incoming framebuffer update.
+	 *
+	 * @return Result
+	 */
+	pub fn update(&mut self) -> io::Result<()> {
+		let wait = !self.requested;
+		if wait {
+			self.request_update()?;
+		}
+		while wait || self.has_pending()? {
+			if self.read_message()? {
+				return self.request_update();
+			}
+		}
+		Ok(())
+	}
+
+	/**
+	 * Read a message from the server.
+	 *
+	 * @return bool (Result) (true if framebuffer is updated)
+	 */
+	fn read_message(&mut self) -> io::Result<bool> {
+		match self.read_u8()? {
+			0 => {
+				self.skip(1)?;
+				for _ in 0..self.read_u16()? {
+					self.read_rectangle()?;
+				}
+				Ok(true)
+			}
+			1 => {
+				self.skip(3)?;
+				let colors = self.read_u16()?;
+				self.skip(usize::from(colors) * 6)?;
+				Ok(false)
+			}
+			2 => Ok(false),
+			3 => {
+				self.skip(3)?;
+				let length = self.read_u32()?;
+				self.skip(length as usize)?;
+				Ok(false)
+			}
+			message => Err(Error::new(
+				ErrorKind::InvalidData,
+				format!("Unknown message type: {}", message),
+			)),
+		}
+	}
+
+	/**
+	 * Read a rectangle of the framebuffer update.
+	 *
+	 * @return Result
+	 */
+	fn read_rectangle(&mut self) -> io::Result<()> {
+		let rect = Geometry::new(
+			i32::from(self.read_u16()?),
+			i32::from(self.read_u16()?),
+			u32::from(self.read_u16()?),
+			u32::from(self.read_u16()?),
+		);
+		let encoding = self.read_u32()? as i32;
+		if encoding == ENCODING_DESKTOP_SIZE {
+			debug!("VNC desktop resized to {}", rect);
+			self.resize(rect.width as u16, rect.height as u16);
+			return Ok(());
+		} else if rect.x as u32 + rect.width > u32::from(self.width)
+			|| rect.y as u32 + rect.height > u32::from(self.height)
+		{
+			return Err(Error::new(
+				ErrorKind::InvalidData,
+				format!("Rectangle out of bounds: {:?}", rect),
+			));
+		}
+		let pixels = match encoding {
+			ENCODING_RAW => {
+				let mut data =
+					vec![0; rect.width as usize * rect.height as usize * 4];
+				self.stream.read_exact(&mut data)?;
+				data.chunks(4)
+					.map(|bgrx| Bgra::from([bgrx[0], bgrx[1], bgrx[2], 255]))
+					.collect()
+			}
+			ENCODING_COPY_RECT => {
+				let source = Geometry::new(
+					i32::from(self.read_u16()?),
+					i32::from(self.read_u16()?),
+					rect.width,
+					rect.height,
+				);
+				if source.x as u32 + source.width > u32::from(self.width)
+					|| source.y as u32 + source.height > u32::from(self.height)
+				{
+					return Err(Error::new(
+						ErrorKind::InvalidData,
+						format!("Copy source out of bounds: {:?}", source),
+					));
+				}
+				self.get_pixels(source)
+			}
+			encoding => {
+				return Err(Error::new(
+					ErrorKind::InvalidData,
+					format!("Unsupported encoding: {}", encoding),
+				))
+			}
+		};
+		let width = usize::from(self.width);
+		for (row, line) in pixels.chunks(rect.width.max(1) as usize).enumerate() {
+			let start = (rect.y as usize + row) * width + rect.x as usize;
+			self.framebuffer[start..start + line.len()].copy_from_slice(line);
+		}
+		Ok(())
+	}
+
+	/**
+	 * Get the pixels of an area from the framebuffer.
+	 *
+	 * @param  area
+	 * @return Vector of Bgra
+	 */
+	fn get_pixels(&self, area: Geometry) -> Vec<Bgra<u8>> {
+		let width = usize::from(self.width);
+		(area.y as usize..area.y as usize + area.height as usize)
+			.flat_map(|y| {
+				let start = y * width + area.x as usize;
+				self.framebuffer[start..start + area.width as usize].iter()
+			})
+			.cloned()
+			.collect()
+	}
+
+	/**
+	 * Get the current framebuffer contents of an area.
+	 *
+	 * @param  area
+	 * @return Tuple (Vector of Bgra, Geometry)
+	 */
+	pub fn get_area(&self, mut area: Geometry) -> (Vec<Bgra<u8>>, Geometry) {
+		area.x = area.x.max(0).min(i32::from(self.width));
+		area.y = area.y.max(0).min(i32::from(self.height));
+		area.width = area.width.min(u32::from(self.width) - area.x as u32);
+		area.height = area.height.min(u32::from(self.height) - area.y as u32);
+		(self.get_pixels(area), area)
+	}
+
+	/* Close the connection. */
+	pub fn close(&self) {
+		self.stream.shutdown(Shutdown::Both).unwrap_or_default();
+	}
+
+	/**
+	 * Read the failure reason sent by the server.
+	 *
+	 * @return Error (Result)
+	 */
+	fn read_reason(&mut self) -> io::Result<Error> {
+		let mut reason = vec![0; self.read_u32()? as usize];
+		self.stream.read_exact(&mut reason)?;
+		Ok(Error::new(
+			ErrorKind::ConnectionRefused,
+			String::from_utf8_lossy(&reason).to_string(),
+		))
+	}
+
+	/**
+	 * Skip the given number of bytes.
+	 *
+	 * @param  length
+	 * @return Result
+	 */
+	fn skip(&mut self, length: usize) -> io::Result<()> {
+		io::copy(&mut (&self.stream).take(length as u64), &mut io::sink())?;
+		Ok(())
+	}
+
+	/* Read a big-endian u8 value. */
+	fn read_u8(&mut self) -> io::Result<u8> {
+		let mut buffer = [0; 1];
+		self.stream.read_exact(&mut buffer)?;
+		Ok(buffer[0])
+	}
+
+	/* Read a big-endian u16 value. */
+	fn read_u16(&mut self) -> io::Result<u16> {
+		let mut buffer = [0; 2];
+		self.stream.read_exact(&mut buffer)?;
+		Ok(u16::from_be_bytes(buffer))
+	}
+
+	/* Read a big-endian u32 value. */
+	fn read_u32(&mut self) -> io::Result<u32> {
+		let mut buffer = [0; 4];
+		self.stream.read_exact(&mut buffer)?;
+		Ok(u32::from_be_bytes(buffer))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vnc::test::TestServer;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_vnc_client() -> io::Result<()> {
+		assert_eq!("localhost:5901", VncClient::get_address("localhost:1"));
+		assert_eq!("localhost:5900", VncClient::get_address("localhost"));
+		assert_eq!("127.0.0.1:6000", VncClient::get_address("127.0.0.1:6000"));
+		let server = TestServer::start(Some("secret"))?;
+		assert!(VncClient::connect(&server.address, None).is_err());
+		let server = TestServer::start(Some("secret"))?;
+		assert_eq!(
+			Some(ErrorKind::PermissionDenied),
+			VncClient::connect(&server.address, Some("wrong"))
+				.err()
+				.map(|e| e.kind())
+		);
+		let server = TestServer::start(Some("secret"))?;
+		let mut client = VncClient::connect(&server.address, Some("secret"))?;
+		assert_eq!("test", client.name);
+		assert_eq!((4, 2), (client.width, client.height));
+		client.update()?;
+		let (data, area) = client.get_area(Geometry::new(0, 0, 4, 2));
+		assert_eq!(Geometry::new(0, 0, 4, 2), area);
+		assert_eq!(Bgra::from([0, 0, 1, 255]), data[1]);
+		assert_eq!(Bgra::from([0, 1, 3, 255]), data[7]);
+		server.send_copy()?;
+		while client.get_area(Geometry::new(0, 0, 1, 1)).0[0]
+			!= Bgra::from([0, 1, 3, 255])
+		{
+			client.update()?;
+		}
+		let (data, area) = client.get_area(Geometry::new(2, 1, 10, 10));
+		assert_eq!(Geometry::new(2, 1, 2, 1), area);
+		assert_eq!(vec![Bgra::from([0, 1, 2, 255]); 1], data[..1].to_vec());
+		client.close();
+		Ok(())
+	}
+}
@@ -0,0 +1,105 @@
+pub mod client;
+pub mod des;
+#[cfg(test)]
+pub mod test;
+pub mod window;
+
+use crate::image::geometry::Geometry;
+use crate::record::settings::RecordWindow;
+use crate::settings::AppSettings;
+use crate::vnc::client::VncClient;
+use crate::vnc::window::Window;
+use crate::window::Access;
+use std::sync::Mutex;
+
+/* VNC (RFB) remote window system */
+#[derive(Debug)]
+pub struct VncSystem<'a> {
+	client: &'static Mutex<VncClient>,
+	settings: &'a AppSettings<'a>,
+}
+
+impl<'a> Access<'a, Window> for VncSystem<'a> {
+	/**
+	 * Connect to the VNC server.
+	 *
+	 * @param  settings
+	 * @return VncSystem (Option)
+	 */
+	fn init(settings: &'a AppSettings<'a>) -> Option<Self> {
+		let vnc = settings.record.vnc?;
+		match VncClient::connect(vnc.address, vnc.password) {
+			Ok(client) => Some(Self {
+				client: Box::leak(Box::new(Mutex::new(client))),
+				settings,
+			}),
+			Err(e) => {
+				error!("Cannot connect to the VNC server: {}", e);
+				None
+			}
+		}
+	}
+
+	/**
+	 * Get the framebuffer area to record.
+	 *
+	 * @return Window (Option)
+	 */
+	fn get_window(&mut self) -> Option<Window> {
+		let client = self.client.lock().expect("Failed to lock the VNC client");
+		let mut area =
+			Geometry::new(0, 0, u32::from(client.width), u32::from(client.height))
+				.with_padding(self.settings.record.padding);
+		match self.settings.record.window {
+			RecordWindow::Focus(Some(size), _) | RecordWindow::Root(Some(size)) => {
+				if size.width != 0 {
+					area.width = area.width.min(size.width);
+				}
+				if size.height != 0 {
+					area.height = area.height.min(size.height);
+				}
+			}
+			_ => {}
+		}
+		debug!("VNC area: {:?}", area);
+		Some(Window::new(self.client, area, self.settings.record))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::args::matches::ArgMatches;
+	use crate::image::padding::Padding;
+	use crate::record::settings::RecordVnc;
+	use crate::vnc::test::TestServer;
+	use crate::window::Capture;
+	use clap::ArgMatches as Args;
+	use image::ExtendedColorType;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_vnc_system() {
+		let args = Args::default();
+		let matches = ArgMatches::new(&args);
+		let mut settings = AppSettings::new(&matches);
+		settings.record.time.countdown = 0;
+		let server = TestServer::start(None).unwrap();
+		let address = Box::leak(server.address.clone().into_boxed_str());
+		settings.record.vnc = Some(RecordVnc::new(address, None));
+		settings.record.padding = Padding::new(1, 0, 0, 1);
+		settings.record.window =
+			RecordWindow::Focus(Some(Geometry::default()), false);
+		let window = VncSystem::init(&settings).unwrap().get_window().unwrap();
+		assert_eq!(Geometry::new(1, 1, 3, 1), window.area);
+		window.show_countdown();
+		let image = window.get_image().unwrap();
+		assert_eq!(Geometry::new(1, 1, 3, 1), image.geometry);
+		assert_eq!(
+			vec![1, 1, 0, 255],
+			image.get_data(ExtendedColorType::Rgba8)[..4].to_vec()
+		);
+		window.release();
+		settings.record.vnc = Some(RecordVnc::new("127.0.0.1:1", None));
+		assert!(VncSystem::init(&settings).is_none());
+	}
+}
@@ -0,0 +1,135 @@
+use crate::vnc::des;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/* Challenge that is sent for VNC authentication */
+const CHALLENGE: [u8; 16] = [7; 16];
+/* Framebuffer size of the testing server */
+const WIDTH: u16 = 4;
+const HEIGHT: u16 = 2;
+
+/* Minimal RFB server for testing */
+#[derive(Debug)]
+pub struct TestServer {
+	pub address: String,
+	stream: Receiver<TcpStream>,
+}
+
+impl TestServer {
+	/**
+	 * Start the server on a random port for a single client.
+	 *
+	 * @param  password (Option)
+	 * @return TestServer (Result)
+	 */
+	pub fn start(password: Option<&'static str>) -> io::Result<Self> {
+		let listener = TcpListener::bind("127.0.0.1:0")?;
+		let address = listener.local_addr()?.to_string();
+		let (sender, receiver) = mpsc::channel();
+		thread::spawn(move || -> io::Result<()> {
+			let (mut stream, _) = listener.accept()?;
+			Self::handshake(&mut stream, password)?;
+			sender.send(stream.try_clone()?).unwrap_or_default();
+			Self::serve(stream)
+		});
+		Ok(Self {
+			address,
+			stream: receiver,
+		})
+	}
+
+	/**
+	 * Perform the handshake with the client (RFB 3.8).
+	 *
+	 * @param  stream
+	 * @param  password (Option)
+	 * @return Result
+	 */
+	fn handshake(stream: &mut TcpStream, password: Option<&str>) -> io::Result<()> {
+		stream.write_all(b"RFB 003.008\n")?;
+		stream.read_exact(&mut [0; 12])?;
+		stream.write_all(&[1, if password.is_some() { 2 } else { 1 }])?;
+		stream.read_exact(&mut [0; 1])?;
+		if let Some(password) = password {
+			stream.write_all(&CHALLENGE)?;
+			let mut response = [0; 16];
+			stream.read_exact(&mut response)?;
+			if response != des::get_vnc_response(password, &CHALLENGE) {
+				let reason = b"Authentication failed";
+				stream.write_all(&1u32.to_be_bytes())?;
+				stream.write_all(&(reason.len() as u32).to_be_bytes())?;
+				stream.write_all(reason)?;
+				return Err(io::Error::new(
+					io::ErrorKind::PermissionDenied,
+					"Authentication failed",
+				));
+			}
+		}
+		stream.write_all(&0u32.to_be_bytes())?;
+		stream.read_exact(&mut [0; 1])?;
+		stream.write_all(&WIDTH.to_be_bytes())?;
+		stream.write_all(&HEIGHT.to_be_bytes())?;
+		stream.write_all(&[0; 16])?;
+		stream.write_all(&4u32.to_be_bytes())?;
+		stream.write_all(b"test")
+	}
+
+	/**
+	 * Handle the client messages.
+	 *
+	 * Responds to the full update requests with a raw
+	 * rectangle where each pixel is colored by its position.
+	 *
+	 * @param  stream
+	 * @return Result
+	 */
+	fn serve(mut stream: TcpStream) -> io::Result<()> {
+		let mut message = [0; 1];
+		while stream.read(&mut message)? != 0 {
+			match message[0] {
+				0 => stream.read_exact(&mut [0; 19])?,
+				2 => {
+					let mut header = [0; 3];
+					stream.read_exact(&mut header)?;
+					let count = u16::from_be_bytes([header[1], header[2]]);
+					stream.read_exact(&mut vec![0; usize::from(count) * 4])?;
+				}
+				3 => {
+					let mut request = [0; 9];
+					stream.read_exact(&mut request)?;
+					if request[0] == 0 {
+						stream.write_all(&[0, 0, 0, 1, 0, 0, 0, 0])?;
+						stream.write_all(&WIDTH.to_be_bytes())?;
+						stream.write_all(&HEIGHT.to_be_bytes())?;
+						stream.write_all(&0i32.to_be_bytes())?;
+						for y in 0..HEIGHT {
+							for x in 0..WIDTH {
+								stream.write_all(&[0, y as u8, x as u8, 0])?;
+							}
+						}
+					}
+				}
+				_ => break,
+			}
+		}
+		Ok(())
+	}
+
+	/**
+	 * Send an update that copies the last pixel to the first one.
+	 *
+	 * @return Result
+	 */
+	pub fn send_copy(&self) -> io::Result<()> {
+		let mut stream = self
+			.stream
+			.recv()
+			.map_err(|e| io::Error::new(io::ErrorKind::NotConnected, e))?;
+		stream.write_all(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 1])?;
+		stream.write_all(&1i32.to_be_bytes())?;
+		stream.write_all(&(WIDTH - 1).to_be_bytes())?;
+		stream.write_all(&(HEIGHT - 1).to_be_bytes())
+	}
+}
@@ -0,0 +1,86 @@
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use crate::record::settings::RecordSettings;
+use crate::vnc::client::VncClient;
+use crate::window::Capture;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/* Framebuffer area of a VNC server */
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+	client: &'static Mutex<VncClient>,
+	pub area: Geometry,
+	settings: RecordSettings,
+}
+
+impl Window {
+	/**
+	 * Create a new Window object.
+	 *
+	 * @param  client
+	 * @param  area
+	 * @param  settings
+	 * @return Window
+	 */
+	pub fn new(
+		client: &'static Mutex<VncClient>,
+		area: Geometry,
+		settings: RecordSettings,
+	) -> Self {
+		Self {
+			client,
+			area,
+			settings,
+		}
+	}
+}
+
+/* Recording implementation for VNC framebuffer */
+impl Capture for Window {
+	/**
+	 * Get the image of the framebuffer area.
+	 *
+	 * @return Image (Option)
+	 */
+	fn get_image(&self) -> Option<Image> {
+		let mut client = self.client.lock().expect("Failed to lock the VNC client");
+		if let Err(e) = client.update() {
+			error!("Failed to update the framebuffer: {}", e);
+			return None;
+		}
+		let (data, area) = client.get_area(self.area);
+		Some(Image::new(data, self.settings.flag.alpha, area))
+	}
+
+	/* Show a countdown on the terminal. */
+	fn show_countdown(&self) {
+		if self.settings.time.countdown != 0 {
+			for i in 0..self.settings.time.countdown {
+				info!(
+					"Starting in {}{}\r",
+					self.settings.time.countdown - i,
+					if self.settings.time.countdown > 9 {
+						" "
+					} else {
+						""
+					}
+				);
+				io::stdout().flush().expect("Failed to flush stdout");
+				thread::sleep(Duration::from_secs(1));
+			}
+			info!("\r");
+		}
+	}
+
+	/* Close the connection to the server. */
+	fn release(&self) {
+		trace!("VNC connection closed.");
+		self.client
+			.lock()
+			.expect("Failed to lock the VNC client")
+			.close();
+	}
+}
@@ -1,3 +1,4 @@
+pub mod pattern;
 pub mod test;
 
 use crate::image::Image;
@@ -0,0 +1,332 @@
+use crate::image::geometry::Geometry;
+use crate::image::Image;
+use crate::record::settings::RecordWindow;
+use crate::settings::AppSettings;
+use crate::window::{Access, Capture};
+use image::Bgra;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* Default size of the pattern window */
+const DEFAULT_SIZE: (u32, u32) = (320, 240);
+/* Bitmaps of the digits (3x5) for the frame counter */
+pub const DIGITS: [u16; 10] = [
+	0b111_101_101_101_111,
+	0b010_110_010_010_111,
+	0b111_001_111_100_111,
+	0b111_001_111_001_111,
+	0b101_101_111_001_001,
+	0b111_100_111_001_111,
+	0b111_100_111_101_111,
+	0b111_001_001_001_001,
+	0b111_101_111_101_111,
+	0b111_101_111_001_111,
+];
+/* Colors of the moving boxes */
+const BOX_COLORS: [[u8; 3]; 4] = [
+	[0xE0, 0x4F, 0x5F],
+	[0x3A, 0xA4, 0x31],
+	[0x3D, 0x8E, 0xE8],
+	[0xF2, 0xC1, 0x4E],
+];
+
+/* Synthetic test pattern */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+	Gradient,
+	Boxes,
+	Noise,
+	Grid,
+	Counter,
+}
+
+/* Implementation for parsing Pattern from a string */
+impl FromStr for Pattern {
+	type Err = &'static str;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"gradient" => Ok(Self::Gradient),
+			"boxes" => Ok(Self::Boxes),
+			"noise" => Ok(Self::Noise),
+			"grid" => Ok(Self::Grid),
+			"counter" => Ok(Self::Counter),
+			_ => Err("Unrecognized pattern"),
+		}
+	}
+}
+
+impl Pattern {
+	/**
+	 * Get the color of a pixel in the given frame.
+	 *
+	 * @param  x
+	 * @param  y
+	 * @param  frame
+	 * @param  geometry
+	 * @return Array of u8 (RGB)
+	 */
+	fn get_pixel(self, x: u32, y: u32, frame: u32, geometry: Geometry) -> [u8; 3] {
+		let (width, height) = (geometry.width.max(1), geometry.height.max(1));
+		match self {
+			Self::Gradient => {
+				let r = ((x + frame % width * 4 % width) * 256 / width) % 256;
+				let g = y * 255 / height;
+				let b = (x + y + frame % 128 * 2) % 256;
+				[r as u8, g as u8, b as u8]
+			}
+			Self::Boxes => {
+				let size = (width.min(height) / 5).max(1);
+				BOX_COLORS
+					.iter()
+					.enumerate()
+					.rev()
+					.find(|(i, _)| {
+						let i = *i as u32;
+						let box_x = triangle(
+							frame.wrapping_mul(3 + i * 2).wrapping_add(i * 37),
+							width.saturating_sub(size),
+						);
+						let box_y = triangle(
+							frame.wrapping_mul(2 + i).wrapping_add(i * 53),
+							height.saturating_sub(size),
+						);
+						x >= box_x
+							&& x < box_x + size && y >= box_y
+							&& y < box_y + size
+					})
+					.map(|(_, color)| *color)
+					.unwrap_or([0x1C, 0x1C, 0x1C])
+			}
+			Self::Noise => {
+				let value = hash(&[x, y, frame]).to_le_bytes();
+				[value[0], value[1], value[2]]
+			}
+			Self::Grid => {
+				let (column, line) = (x / 6, (y / 10).wrapping_add(frame));
+				let (cell_x, cell_y) = (x % 6, y % 10);
+				if column < hash(&[line]) % (width / 6).max(1)
+					&& hash(&[line, column]) & 0xFF > 40
+					&& cell_x < 5 && cell_y > 0
+					&& cell_y < 9 && hash(&[line, column, cell_x, cell_y]) & 0xFF > 85
+				{
+					[0xCC, 0xCC, 0xCC]
+				} else {
+					[0x1C, 0x1C, 0x1C]
+				}
+			}
+			Self::Counter => {
+				let text = frame.to_string();
+				let columns = text.len() as u32 * 4 - 1;
+				let scale = (width / (columns + 2)).min(height / 7).max(1);
+				let offset_x = (width.saturating_sub(columns * scale) / 2) as i64;
+				let offset_y = (height.saturating_sub(5 * scale) / 2) as i64;
+				let cell_x = (i64::from(x) - offset_x).div_euclid(i64::from(scale));
+				let cell_y = (i64::from(y) - offset_y).div_euclid(i64::from(scale));
+				let lit = cell_x >= 0
+					&& cell_y >= 0 && cell_x < i64::from(columns)
+					&& cell_y < 5 && cell_x % 4 != 3
+					&& text
+						.as_bytes()
+						.get((cell_x / 4) as usize)
+						.map(|digit| {
+							(DIGITS[usize::from(digit - b'0')]
+								>> (14 - (cell_y * 3 + cell_x % 4)))
+								& 1 == 1
+						})
+						.unwrap_or(false);
+				if lit {
+					[0xFF, 0xFF, 0xFF]
+				} else {
+					[0x1C, (y * 64 / height) as u8, 0x1C]
+				}
+			}
+		}
+	}
+}
+
+/**
+ * Get the position on a triangle wave for bouncing between 0 and max.
+ *
+ * @param  t
+ * @param  max
+ * @return u32
+ */
+fn triangle(t: u32, max: u32) -> u32 {
+	if max == 0 {
+		return 0;
+	}
+	let position = t % (2 * max);
+	if position > max {
+		2 * max - position
+	} else {
+		position
+	}
+}
+
+/**
+ * Hash the given values into a pseudo-random number.
+ *
+ * @param  values
+ * @return u32
+ */
+fn hash(values: &[u32]) -> u32 {
+	values.iter().fold(0x811C_9DC5, |hash, value| {
+		let mut x = hash ^ value;
+		x ^= x >> 16;
+		x = x.wrapping_mul(0x7FEB_352D);
+		x ^= x >> 15;
+		x = x.wrapping_mul(0x846C_A68B);
+		x ^ (x >> 16)
+	})
+}
+
+/* Window that generates animated test patterns */
+#[derive(Clone, Copy, Debug)]
+pub struct PatternWindow {
+	pub pattern: Pattern,
+	pub geometry: Geometry,
+	frame: &'static AtomicUsize,
+}
+
+impl PatternWindow {
+	/**
+	 * Create a new PatternWindow object.
+	 *
+	 * @param  pattern
+	 * @param  geometry
+	 * @return PatternWindow
+	 */
+	pub fn new(pattern: Pattern, geometry: Geometry) -> Self {
+		Self {
+			pattern,
+			geometry,
+			frame: Box::leak(Box::new(AtomicUsize::new(0))),
+		}
+	}
+
+	/**
+	 * Get the image of the given frame.
+	 *
+	 * @param  frame
+	 * @return Image
+	 */
+	pub fn get_frame(&self, frame: u32) -> Image {
+		let mut data = Vec::with_capacity(
+			self.geometry.width as usize * self.geometry.height as usize,
+		);
+		for y in 0..self.geometry.height {
+			for x in 0..self.geometry.width {
+				let [r, g, b] = self.pattern.get_pixel(x, y, frame, self.geometry);
+				data.push(Bgra::from([b, g, r, 255]));
+			}
+		}
+		Image::new(data, false, self.geometry)
+	}
+}
+
+/* Recording implementation for PatternWindow */
+impl Capture for PatternWindow {
+	/**
+	 * Get the image of the next frame.
+	 *
+	 * @return Image (Option)
+	 */
+	fn get_image(&self) -> Option<Image> {
+		Some(self.get_frame(self.frame.fetch_add(1, Ordering::SeqCst) as u32))
+	}
+
+	/* Do not show countdown for pattern window. */
+	fn show_countdown(&self) {}
+
+	/* Do not do anything with respect to release. */
+	fn release(&self) {}
+}
+
+/* Source of the pattern windows */
+#[derive(Debug)]
+pub struct PatternSystem<'a> {
+	settings: &'a AppSettings<'a>,
+}
+
+impl<'a> Access<'a, PatternWindow> for PatternSystem<'a> {
+	/**
+	 * Initialize the pattern source.
+	 *
+	 * @param  settings
+	 * @return PatternSystem (Option)
+	 */
+	fn init(settings: &'a AppSettings<'a>) -> Option<Self> {
+		Some(Self { settings })
+	}
+
+	/**
+	 * Get the window with the pattern from settings.
+	 *
+	 * @return PatternWindow (Option)
+	 */
+	fn get_window(&mut self) -> Option<PatternWindow> {
+		let mut geometry = Geometry::new(0, 0, DEFAULT_SIZE.0, DEFAULT_SIZE.1);
+		match self.settings.record.window {
+			RecordWindow::Focus(Some(size), _) | RecordWindow::Root(Some(size)) => {
+				if size.width != 0 {
+					geometry.width = size.width;
+				}
+				if size.height != 0 {
+					geometry.height = size.height;
+				}
+			}
+			_ => {}
+		}
+		Some(PatternWindow::new(self.settings.record.pattern?, geometry))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::args::matches::ArgMatches;
+	use clap::ArgMatches as Args;
+	use image::ExtendedColorType;
+	use pretty_assertions::{assert_eq, assert_ne};
+	#[test]
+	fn test_pattern_window() {
+		let geometry = Geometry::new(0, 0, 64, 48);
+		for pattern in &["gradient", "boxes", "noise", "grid", "counter"] {
+			let window = PatternWindow::new(pattern.parse().unwrap(), geometry);
+			let images = [window.get_frame(0), window.get_frame(1)];
+			assert_eq!(geometry, images[0].geometry);
+			assert_eq!(
+				64 * 48 * 3,
+				images[0].get_data(ExtendedColorType::Rgb8).len()
+			);
+			assert_ne!(
+				images[0].get_data(ExtendedColorType::Rgb8),
+				images[1].get_data(ExtendedColorType::Rgb8)
+			);
+			assert_eq!(
+				images[1].get_data(ExtendedColorType::Rgb8),
+				window.get_frame(1).get_data(ExtendedColorType::Rgb8)
+			);
+			assert_eq!(geometry, window.get_frame(u32::MAX).geometry);
+		}
+		assert!("x".parse::<Pattern>().is_err());
+		let window = PatternWindow::new(Pattern::Counter, Geometry::new(0, 0, 3, 5));
+		assert_eq!(
+			vec![255, 255, 255, 28, 12, 28, 255, 255, 255],
+			window.get_frame(8).get_data(ExtendedColorType::Rgb8)[9..18].to_vec()
+		);
+		assert_eq!(0, window.frame.load(Ordering::SeqCst));
+		window.get_image();
+		assert_eq!(1, window.frame.load(Ordering::SeqCst));
+		let args = Args::default();
+		let matches = ArgMatches::new(&args);
+		let mut settings = AppSettings::new(&matches);
+		settings.record.pattern = Some(Pattern::Noise);
+		settings.record.window = RecordWindow::Root(Some(Geometry::new(0, 0, 8, 0)));
+		let window = PatternSystem::init(&settings)
+			.unwrap()
+			.get_window()
+			.unwrap();
+		assert_eq!(Geometry::new(0, 0, 8, 240), window.geometry);
+	}
+}
@@ -0,0 +1,154 @@
+use crate::daemon::settings::DaemonSettings;
+use crate::daemon::{Hotkey, Modifier};
+use crate::x11::display::Display;
+use crate::x11::window::Window;
+use std::ffi::CString;
+use std::io::{Error, ErrorKind};
+use std::mem::MaybeUninit;
+use std::os::raw::c_uint;
+use std::thread;
+use x11::xlib;
+
+/* Lock masks to ignore while grabbing the hotkeys (CapsLock and NumLock) */
+const LOCK_MASKS: [c_uint; 4] = [
+	0,
+	xlib::LockMask,
+	xlib::Mod2Mask,
+	xlib::LockMask | xlib::Mod2Mask,
+];
+/* Modifier masks that are checked while matching the hotkeys */
+const MODIFIER_MASK: c_uint =
+	xlib::ShiftMask | xlib::ControlMask | xlib::Mod1Mask | xlib::Mod4Mask;
+
+/* X11 daemon that listens for the global hotkeys */
+#[derive(Debug)]
+pub struct Daemon<'a> {
+	display: Display,
+	settings: &'a DaemonSettings,
+}
+
+impl<'a> Daemon<'a> {
+	/**
+	 * Create a new Daemon object.
+	 *
+	 * @param  settings
+	 * @return Daemon (Option)
+	 */
+	pub fn new(settings: &'a DaemonSettings) -> Option<Self> {
+		let display = Display::open(None)?;
+		unsafe { xlib::XSetErrorHandler(Some(super::handle_x11_errors)) };
+		Some(Self { display, settings })
+	}
+
+	/**
+	 * Get the modifier mask of the given modifiers.
+	 *
+	 * @param  modifiers
+	 * @return c_uint
+	 */
+	fn get_mask(modifiers: &[Modifier]) -> c_uint {
+		modifiers.iter().fold(0, |mask, modifier| {
+			mask | match modifier {
+				Modifier::Shift => xlib::ShiftMask,
+				Modifier::Control => xlib::ControlMask,
+				Modifier::Alt => xlib::Mod1Mask,
+				Modifier::Super => xlib::Mod4Mask,
+			}
+		})
+	}
+
+	/**
+	 * Grab the hotkeys on the root window.
+	 *
+	 * @param  root_window
+	 * @return Vector of (keycode, mask, Hotkey) (Result)
+	 */
+	fn grab_hotkeys(
+		&self,
+		root_window: Window,
+	) -> Result<Vec<(c_uint, c_uint, &'a Hotkey)>, Error> {
+		let mut hotkeys = Vec::new();
+		for hotkey in &self.settings.hotkeys {
+			let key = CString::new(hotkey.key.as_str())
+				.map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+			let keysym = unsafe { xlib::XStringToKeysym(key.as_ptr()) };
+			let keycode = c_uint::from(unsafe {
+				xlib::XKeysymToKeycode(self.display.inner, keysym)
+			});
+			if keysym == 0 || keycode == 0 {
+				return Err(Error::new(
+					ErrorKind::InvalidInput,
+					format!("Invalid key ({})", hotkey.key),
+				));
+			}
+			let mask = Self::get_mask(&hotkey.modifiers);
+			for lock_mask in &LOCK_MASKS {
+				root_window.grab_key_with_modifiers(keysym, mask | lock_mask);
+			}
+			debug!("Grabbed the hotkey: {:?}", hotkey);
+			hotkeys.push((keycode, mask, hotkey));
+		}
+		unsafe { xlib::XSync(self.display.inner, xlib::False) };
+		Ok(hotkeys)
+	}
+
+	/**
+	 * Listen for the hotkeys and run the corresponding profiles.
+	 *
+	 * @return Result
+	 */
+	pub fn run(&self) -> Result<(), Error> {
+		if self.settings.hotkeys.is_empty() {
+			return Err(Error::new(ErrorKind::InvalidInput, "No hotkeys specified"));
+		}
+		let hotkeys = self.grab_hotkeys(self.display.get_root_window())?;
+		info!("Listening for {} hotkey(s)...", hotkeys.len());
+		let mut event = MaybeUninit::<xlib::XEvent>::uninit();
+		loop {
+			let key_event = unsafe {
+				xlib::XNextEvent(self.display.inner, event.as_mut_ptr());
+				if (*event.as_ptr()).get_type() != xlib::KeyPress {
+					continue;
+				}
+				(*event.as_ptr()).key
+			};
+			let hotkey = hotkeys.iter().find(|(keycode, mask, _)| {
+				key_event.keycode == *keycode
+					&& key_event.state & MODIFIER_MASK == *mask
+			});
+			if let Some(profile) =
+				hotkey.and_then(|(_, _, hotkey)| self.settings.get_profile(hotkey))
+			{
+				match profile.run(&self.settings.args) {
+					Ok(mut child) => {
+						let name = profile.name.clone();
+						thread::spawn(move || match child.wait() {
+							Ok(status) if status.success() => {
+								info!("Profile finished: {}", name)
+							}
+							Ok(status) => {
+								warn!("Profile failed: {} ({})", name, status)
+							}
+							Err(e) => error!("Profile failed: {} ({})", name, e),
+						});
+					}
+					Err(e) => error!("Failed to run the profile: {}", e),
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_daemon_mask() {
+		assert_eq!(0, Daemon::get_mask(&[]));
+		assert_eq!(
+			xlib::ControlMask | xlib::Mod4Mask,
+			Daemon::get_mask(&[Modifier::Control, Modifier::Super])
+		);
+	}
+}
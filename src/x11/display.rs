@@ -1,9 +1,14 @@
 use crate::image::geometry::Geometry;
+use crate::image::padding::Padding;
+use crate::measure::Measurement;
+use crate::pick::PixelColor;
 use crate::record::fps::FpsClock;
 use crate::record::settings::{RecordSettings, RecordWindow};
 use crate::util::state::InputState;
+use crate::x11::loupe::Loupe;
+use crate::x11::output::Output;
 use crate::x11::window::Window;
-use device_query::{DeviceQuery, Keycode};
+use device_query::Keycode;
 use std::convert::TryInto;
 use std::ffi::CString;
 use std::io::{self, Write};
@@ -28,6 +33,7 @@ const DEFAULT_FONT: &str = "-misc-fixed-*-*-*-*-*-*-*-*-*-*-*-*";
 pub struct Display {
 	pub inner: *mut xlib::Display,
 	pub font: Option<*mut xlib::XFontStruct>,
+	pub monitors: Option<&'static [Geometry]>,
 	pub settings: RecordSettings,
 }
 
@@ -48,6 +54,7 @@ impl Display {
 				Self {
 					inner: display,
 					font: None,
+					monitors: None,
 					settings: settings.unwrap_or_default(),
 				}
 				.set_font(),
@@ -158,11 +165,16 @@ impl Display {
 		let (window, mut size) = self.get_window();
 		if let RecordWindow::Root(_) = self.settings.window {
 			if let Some(monitor) = self.settings.flag.monitor {
-				let crtc = window.get_crtc_info();
-				let geometry = crtc
-					.get(monitor.checked_sub(1).unwrap_or_default())
-					.expect("Invalid monitor number");
-				size = *geometry;
+				let monitors = Output::select(&window.get_outputs(), monitor)
+					.expect("Invalid monitor")
+					.iter()
+					.map(|output| output.geometry)
+					.collect::<Vec<Geometry>>();
+				let geometry = Output::get_bounds(&monitors);
+				if monitors.len() > 1 {
+					self.monitors = Some(Box::leak(monitors.into_boxed_slice()));
+				}
+				size = geometry;
 				self.settings.padding.left =
 					geometry.x.try_into().unwrap_or_default();
 				self.settings.padding.top =
@@ -213,10 +225,21 @@ impl Display {
 				CString::new(DEFAULT_FONT).unwrap_or_default().as_ptr(),
 			)
 		});
+		let (mut level, mut keys) = (0, Vec::new());
 		let start_time = Instant::now();
 		while !input_state.check_action() {
 			thread::sleep(Duration::from_millis(self.settings.time.interval));
-			window = self.get_window().0;
+			let pressed_keys = input_state.get_keys();
+			if pressed_keys != keys {
+				if Self::update_level(&pressed_keys, &mut level) {
+					debug!("Window level: {}", level);
+				}
+				keys = pressed_keys;
+			}
+			let (nested_window, nested_level) =
+				self.get_nested_window(self.get_window().0, level);
+			window = nested_window;
+			level = nested_level;
 			if self.settings.flag.select {
 				window.draw_borders();
 				window.show_text_centered(Some(window.area.to_string()), font);
@@ -269,6 +292,258 @@ impl Display {
 		}
 	}
 
+	/**
+	 * Get the window at the given level of the window hierarchy.
+	 *
+	 * Positive levels descend into the child windows under the
+	 * pointer whereas negative levels climb to the parent windows.
+	 *
+	 * @param  window
+	 * @param  level
+	 * @return Tuple (Window, i32)
+	 */
+	fn get_nested_window(&self, mut window: Window, level: i32) -> (Window, i32) {
+		let mut depth = 0;
+		while depth != level {
+			let next_window = if level > 0 {
+				window.get_child_at_pointer()
+			} else {
+				unsafe { window.get_parent() }.filter(|parent| parent.xid != 0)
+			};
+			match next_window {
+				Some(next_window) => {
+					window = next_window;
+					depth += level.signum();
+				}
+				None => break,
+			}
+		}
+		(window, depth)
+	}
+
+	/**
+	 * Update the level of the window in hierarchy on associated key presses.
+	 *
+	 * @param  keys
+	 * @param  level
+	 * @return bool
+	 */
+	fn update_level(keys: &[Keycode], level: &mut i32) -> bool {
+		match keys {
+			[Keycode::LAlt, Keycode::PageDown]
+			| [Keycode::PageDown, Keycode::LAlt] => {
+				*level += 1;
+				true
+			}
+			[Keycode::LAlt, Keycode::PageUp] | [Keycode::PageUp, Keycode::LAlt] => {
+				*level -= 1;
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/**
+	 * Select an area of the root window by dragging the mouse.
+	 *
+	 * @param  input_state
+	 * @return Window (Option)
+	 */
+	pub fn select_area(&mut self, input_state: &InputState) -> Option<Window> {
+		let root_window = self.get_root_window();
+		let gc = unsafe { root_window.get_xor_gc() };
+		let loupe = if self.settings.flag.loupe {
+			Some(Loupe::create(*self, root_window.geometry))
+		} else {
+			None
+		};
+		let (mut start, mut area, mut pointer) = (None, None, None);
+		let start_time = Instant::now();
+		let selection = loop {
+			thread::sleep(Duration::from_millis(self.settings.time.interval));
+			let mouse = input_state.get_mouse();
+			if input_state.check_cancel_keys() {
+				warn!("User interrupt detected.");
+				break None;
+			} else if start_time.elapsed().as_secs() > self.settings.time.timeout {
+				warn!("The operation timed out.");
+				break None;
+			}
+			let pressed = mouse.button_pressed.get(1).copied().unwrap_or(false);
+			match start {
+				None if pressed => start = Some(mouse.coords),
+				Some(_) if !pressed => break area,
+				_ => {}
+			}
+			if pointer == Some(mouse.coords) {
+				continue;
+			}
+			pointer = Some(mouse.coords);
+			let text = if let Some(point) = start {
+				let geometry = Geometry::from_points(point, mouse.coords);
+				if let Some(area) = area {
+					root_window.draw_selection(gc, area);
+				}
+				root_window.draw_selection(gc, geometry);
+				area = Some(geometry);
+				format!("{}+{}+{}", geometry, geometry.x, geometry.y)
+			} else {
+				format!("{},{}", mouse.coords.0, mouse.coords.1)
+			};
+			if let Some(loupe) = loupe {
+				loupe.update(mouse.coords, &text);
+			}
+			info!(" Selected area -> [{}]{:<10}\r#", text, " ");
+			io::stdout().flush().expect("Failed to flush stdout");
+		};
+		if let Some(area) = area {
+			root_window.draw_selection(gc, area);
+		}
+		if let Some(loupe) = loupe {
+			loupe.destroy();
+		}
+		unsafe { xlib::XFreeGC(self.inner, gc) };
+		debug!("\n");
+		match selection {
+			Some(area) if area.width != 0 && area.height != 0 => {
+				debug!("Selected area: {:?}", area);
+				self.settings.padding = Padding::new(
+					area.y.try_into().unwrap_or_default(),
+					root_window
+						.geometry
+						.width
+						.saturating_sub(area.x as u32 + area.width),
+					root_window
+						.geometry
+						.height
+						.saturating_sub(area.y as u32 + area.height),
+					area.x.try_into().unwrap_or_default(),
+				);
+				Some(self.get_root_window())
+			}
+			_ => None,
+		}
+	}
+
+	/**
+	 * Measure the distance between two clicked points or a dragged area.
+	 *
+	 * @param  input_state
+	 * @return Measurement (Option)
+	 */
+	pub fn measure(&self, input_state: &InputState) -> Option<Measurement> {
+		let root_window = self.get_root_window();
+		let gc = unsafe { root_window.get_xor_gc() };
+		let (mut start, mut current, mut pointer) = (None, None, None);
+		let mut pressed = false;
+		let start_time = Instant::now();
+		let measurement = loop {
+			thread::sleep(Duration::from_millis(self.settings.time.interval));
+			let mouse = input_state.get_mouse();
+			if input_state.check_cancel_keys() {
+				warn!("User interrupt detected.");
+				break None;
+			} else if start_time.elapsed().as_secs() > self.settings.time.timeout {
+				warn!("The operation timed out.");
+				break None;
+			}
+			let was_pressed = pressed;
+			pressed = mouse.button_pressed.get(1).copied().unwrap_or(false);
+			match start {
+				None if pressed && !was_pressed => start = Some(mouse.coords),
+				Some(point) if pressed && !was_pressed => {
+					break Some(Measurement::new(point, mouse.coords, false))
+				}
+				Some(point) if !pressed && was_pressed && point != mouse.coords => {
+					break Some(Measurement::new(point, mouse.coords, true))
+				}
+				_ => {}
+			}
+			if pointer == Some(mouse.coords) {
+				continue;
+			}
+			pointer = Some(mouse.coords);
+			if let Some(point) = start {
+				let measurement = Measurement::new(point, mouse.coords, pressed);
+				if let Some(current) = current {
+					root_window.draw_measurement(gc, current);
+				}
+				root_window.draw_measurement(gc, measurement);
+				current = Some(measurement);
+				info!(" Measurement -> [{}]{:<10}\r#", measurement, " ");
+			} else {
+				info!(
+					" Start point -> [{},{}]{:<10}\r#",
+					mouse.coords.0, mouse.coords.1, " "
+				);
+			}
+			io::stdout().flush().expect("Failed to flush stdout");
+		};
+		if let Some(current) = current {
+			root_window.draw_measurement(gc, current);
+		}
+		unsafe { xlib::XFreeGC(self.inner, gc) };
+		debug!("\n");
+		measurement
+	}
+
+	/**
+	 * Pick the colors of the clicked pixels on the root window.
+	 *
+	 * @param  input_state
+	 * @param  count
+	 * @return Vector of PixelColor
+	 */
+	pub fn pick_colors(
+		&self,
+		input_state: &InputState,
+		count: usize,
+	) -> Vec<PixelColor> {
+		let root_window = self.get_root_window();
+		let loupe = Loupe::create(*self, root_window.geometry);
+		let mut colors = Vec::new();
+		let (mut pointer, mut pressed) = (None, false);
+		let start_time = Instant::now();
+		while colors.len() < count {
+			thread::sleep(Duration::from_millis(self.settings.time.interval));
+			let mouse = input_state.get_mouse();
+			if input_state.check_cancel_keys() {
+				warn!("User interrupt detected.");
+				colors.clear();
+				break;
+			} else if input_state.check_action() {
+				break;
+			} else if start_time.elapsed().as_secs() > self.settings.time.timeout {
+				warn!("The operation timed out.");
+				break;
+			}
+			let was_pressed = pressed;
+			pressed = mouse.button_pressed.get(1).copied().unwrap_or(false);
+			let clicked = pressed && !was_pressed;
+			if pointer == Some(mouse.coords) && !clicked {
+				continue;
+			}
+			pointer = Some(mouse.coords);
+			if let Some(pixel) = root_window.get_pixel(mouse.coords) {
+				let color = PixelColor::new(
+					mouse.coords.0,
+					mouse.coords.1,
+					[pixel.0[2], pixel.0[1], pixel.0[0], pixel.0[3]],
+				);
+				loupe.update(mouse.coords, &color.get_hex());
+				info!(" Picked color -> [{}]{:<10}\r#", color, " ");
+				io::stdout().flush().expect("Failed to flush stdout");
+				if clicked {
+					debug!("Picked color: {:?}", color);
+					colors.push(color);
+				}
+			}
+		}
+		loupe.destroy();
+		debug!("\n");
+		colors
+	}
+
 	/**
 	 * Update padding to set the given width and height.
 	 *
@@ -305,7 +580,7 @@ impl Display {
 		let mut reset_area = false;
 		let modifiers = self.settings.padding.get_modifiers();
 		for (value, increase, decrease) in modifiers {
-			match input_state.state.get_keys().as_slice() {
+			match input_state.get_keys().as_slice() {
 				[Keycode::R, Keycode::LAlt] => reset_area = true,
 				[Keycode::LAlt, key] | [key, Keycode::LAlt] => {
 					if (key == &increase[0] || key == &increase[1])
@@ -383,6 +658,20 @@ mod tests {
 		display
 			.set_focused_window(display.get_root_window().xid, xlib::RevertToParent);
 		display.update_padding(Geometry::new(0, 0, 10, 10), Geometry::default());
+		let mut level = 0;
+		assert!(Display::update_level(
+			&[Keycode::LAlt, Keycode::PageDown],
+			&mut level
+		));
+		assert!(Display::update_level(
+			&[Keycode::PageUp, Keycode::LAlt],
+			&mut level
+		));
+		assert!(!Display::update_level(&[Keycode::PageUp], &mut level));
+		assert_eq!(0, level);
+		let root_window = display.get_root_window();
+		let (window, level) = display.get_nested_window(root_window, -1);
+		assert_eq!((root_window.xid, 0), (window.xid, level));
 		assert_eq!(
 			display.get_root_window().xid,
 			display.get_focused_window(false).unwrap().xid
@@ -0,0 +1,217 @@
+use image::Bgra;
+use std::slice;
+use x11::xlib;
+
+/* Pixel layout of an XImage */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelFormat {
+	pub depth: u32,
+	pub bits_per_pixel: u32,
+	pub bytes_per_line: usize,
+	pub msb_first: bool,
+	pub red_mask: u32,
+	pub green_mask: u32,
+	pub blue_mask: u32,
+}
+
+/* Implementation for getting the PixelFormat of an XImage */
+impl From<&xlib::XImage> for PixelFormat {
+	fn from(image: &xlib::XImage) -> Self {
+		Self {
+			depth: image.depth as u32,
+			bits_per_pixel: image.bits_per_pixel as u32,
+			bytes_per_line: image.bytes_per_line as usize,
+			msb_first: image.byte_order == xlib::MSBFirst,
+			red_mask: image.red_mask as u32,
+			green_mask: image.green_mask as u32,
+			blue_mask: image.blue_mask as u32,
+		}
+	}
+}
+
+impl PixelFormat {
+	/**
+	 * Check if the format matches the layout of Bgra<u8>.
+	 *
+	 * @return bool
+	 */
+	fn is_bgra(&self) -> bool {
+		self.bits_per_pixel == 32
+			&& !self.msb_first
+			&& self.red_mask == 0x00FF_0000
+			&& self.green_mask == 0x0000_FF00
+			&& self.blue_mask == 0x0000_00FF
+	}
+
+	/**
+	 * Get the mask of the alpha channel (for 32-bit depth).
+	 *
+	 * @return u32
+	 */
+	fn get_alpha_mask(&self) -> u32 {
+		if self.depth == 32 && self.bits_per_pixel == 32 {
+			!(self.red_mask | self.green_mask | self.blue_mask)
+		} else {
+			0
+		}
+	}
+
+	/**
+	 * Convert the raw image data to Bgra pixels.
+	 *
+	 * @param  data
+	 * @param  width
+	 * @param  height
+	 * @return Vector of Bgra (Option)
+	 */
+	pub fn to_bgra(
+		self,
+		data: &[u8],
+		width: usize,
+		height: usize,
+	) -> Option<Vec<Bgra<u8>>> {
+		let bytes_per_pixel = self.bits_per_pixel as usize / 8;
+		if ![8, 16, 24, 32].contains(&self.bits_per_pixel)
+			|| self.red_mask == 0
+			|| self.green_mask == 0
+			|| self.blue_mask == 0
+			|| self.bytes_per_line < width * bytes_per_pixel
+			|| data.len() < self.bytes_per_line * height
+		{
+			warn!("Unsupported pixel format: {:?}", self);
+			return None;
+		}
+		let alpha_mask = self.get_alpha_mask();
+		let mut pixels = Vec::with_capacity(width * height);
+		for row in data.chunks(self.bytes_per_line).take(height) {
+			if self.is_bgra() {
+				pixels.extend(row[..width * 4].chunks(4).map(|bgra| {
+					Bgra::from([
+						bgra[0],
+						bgra[1],
+						bgra[2],
+						if alpha_mask != 0 { bgra[3] } else { 255 },
+					])
+				}));
+				continue;
+			}
+			for bytes in row[..width * bytes_per_pixel].chunks(bytes_per_pixel) {
+				let value = if self.msb_first {
+					bytes.iter().fold(0, |v, b| (v << 8) | u32::from(*b))
+				} else {
+					bytes.iter().rev().fold(0, |v, b| (v << 8) | u32::from(*b))
+				};
+				pixels.push(Bgra::from([
+					get_channel(value, self.blue_mask),
+					get_channel(value, self.green_mask),
+					get_channel(value, self.red_mask),
+					if alpha_mask != 0 {
+						get_channel(value, alpha_mask)
+					} else {
+						255
+					},
+				]));
+			}
+		}
+		Some(pixels)
+	}
+
+	/**
+	 * Get the pixels of an XImage.
+	 *
+	 * @param  image
+	 * @return Vector of Bgra (Option)
+	 */
+	pub unsafe fn get_pixels(image: &xlib::XImage) -> Option<Vec<Bgra<u8>>> {
+		let format = Self::from(image);
+		format.to_bgra(
+			slice::from_raw_parts(
+				image.data as *const u8,
+				format.bytes_per_line * image.height as usize,
+			),
+			image.width as usize,
+			image.height as usize,
+		)
+	}
+}
+
+/**
+ * Get the 8-bit value of a channel from the pixel value.
+ *
+ * @param  value
+ * @param  mask
+ * @return u8
+ */
+fn get_channel(value: u32, mask: u32) -> u8 {
+	let shift = mask.trailing_zeros();
+	let max = u64::from(mask >> shift);
+	let channel = u64::from((value & mask) >> shift);
+	((channel * 255 + max / 2) / max) as u8
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	fn get_format(
+		depth: u32,
+		bits_per_pixel: u32,
+		bytes_per_line: usize,
+		msb_first: bool,
+		masks: (u32, u32, u32),
+	) -> PixelFormat {
+		PixelFormat {
+			depth,
+			bits_per_pixel,
+			bytes_per_line,
+			msb_first,
+			red_mask: masks.0,
+			green_mask: masks.1,
+			blue_mask: masks.2,
+		}
+	}
+	#[test]
+	fn test_pixel_format() {
+		let rgb888 = (0x00FF_0000, 0x0000_FF00, 0x0000_00FF);
+		let format = get_format(24, 32, 8, false, rgb888);
+		assert_eq!(
+			Some(vec![Bgra::from([1, 2, 3, 255]), Bgra::from([5, 6, 7, 255])]),
+			format.to_bgra(&[1, 2, 3, 4, 5, 6, 7, 8], 2, 1)
+		);
+		let format = get_format(32, 32, 8, false, rgb888);
+		assert_eq!(
+			Some(vec![Bgra::from([1, 2, 3, 4]), Bgra::from([5, 6, 7, 8])]),
+			format.to_bgra(&[1, 2, 3, 4, 5, 6, 7, 8], 2, 1)
+		);
+		let format = get_format(32, 32, 4, true, rgb888);
+		assert_eq!(
+			Some(vec![Bgra::from([4, 3, 2, 1])]),
+			format.to_bgra(&[1, 2, 3, 4], 1, 1)
+		);
+		let format = get_format(24, 24, 8, false, rgb888);
+		assert_eq!(
+			Some(vec![Bgra::from([1, 2, 3, 255]), Bgra::from([4, 5, 6, 255])]),
+			format.to_bgra(&[1, 2, 3, 4, 5, 6, 0, 0, 9, 9, 9, 9, 9, 9, 0, 0], 2, 1)
+		);
+		let format = get_format(16, 16, 2, false, (0xF800, 0x07E0, 0x001F));
+		assert_eq!(
+			Some(vec![Bgra::from([255, 0, 255, 255])]),
+			format.to_bgra(&[0x1F, 0xF8], 1, 1)
+		);
+		let format = get_format(16, 16, 2, true, (0xF800, 0x07E0, 0x001F));
+		assert_eq!(
+			Some(vec![Bgra::from([0, 255, 0, 255])]),
+			format.to_bgra(&[0x07, 0xE0], 1, 1)
+		);
+		let format =
+			get_format(30, 32, 4, false, (0x3FF0_0000, 0x000F_FC00, 0x0000_03FF));
+		assert_eq!(
+			Some(vec![Bgra::from([0, 128, 255, 255])]),
+			format.to_bgra(&0x3FF8_0000_u32.to_le_bytes(), 1, 1)
+		);
+		let format = get_format(8, 8, 1, false, (0, 0, 0));
+		assert_eq!(None, format.to_bgra(&[0], 1, 1));
+		let format = get_format(24, 32, 4, false, rgb888);
+		assert_eq!(None, format.to_bgra(&[0, 0], 1, 1));
+	}
+}
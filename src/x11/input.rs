@@ -0,0 +1,248 @@
+use crate::util::event::{InputEventKind, InputEvents};
+use device_query::Keycode;
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+use std::ptr;
+use std::str::FromStr;
+use std::thread;
+use x11::{xinput2, xlib};
+
+/* Offset between the X keycodes and the kernel keycodes */
+const KEYCODE_OFFSET: u8 = 8;
+
+/* Listener of the raw input events via XInput2 */
+#[derive(Debug)]
+pub struct XInput {
+	display: *mut xlib::Display,
+	opcode: c_int,
+	events: &'static InputEvents,
+}
+
+/* Implementation for thread-safe usage */
+unsafe impl Send for XInput {}
+
+impl XInput {
+	/**
+	 * Start listening for the raw input events on a separate thread.
+	 *
+	 * @return InputEvents (Option)
+	 */
+	pub fn start() -> Option<&'static InputEvents> {
+		let input = unsafe { Self::open()? };
+		let events = input.events;
+		thread::spawn(move || unsafe { input.listen() });
+		debug!("Listening for the input events via XInput2...");
+		Some(events)
+	}
+
+	/**
+	 * Open a display and select the raw input events.
+	 *
+	 * @return XInput (Option)
+	 */
+	unsafe fn open() -> Option<Self> {
+		let display = xlib::XOpenDisplay(ptr::null());
+		if display.is_null() {
+			return None;
+		}
+		let extension = CString::new("XInputExtension").ok()?;
+		let (mut opcode, mut event, mut error) = (0, 0, 0);
+		let (mut major, mut minor) = (2, 0);
+		if xlib::XQueryExtension(
+			display,
+			extension.as_ptr(),
+			&mut opcode,
+			&mut event,
+			&mut error,
+		) == xlib::False
+			|| xinput2::XIQueryVersion(display, &mut major, &mut minor)
+				!= xlib::Success as c_int
+		{
+			warn!("XInput2 is not available, falling back to polling.");
+			xlib::XCloseDisplay(display);
+			return None;
+		}
+		let mut mask = [0; 3];
+		for event in &[
+			xinput2::XI_RawKeyPress,
+			xinput2::XI_RawKeyRelease,
+			xinput2::XI_RawButtonPress,
+			xinput2::XI_RawButtonRelease,
+			xinput2::XI_RawMotion,
+		] {
+			xinput2::XISetMask(&mut mask, *event);
+		}
+		let mut event_mask = xinput2::XIEventMask {
+			deviceid: xinput2::XIAllMasterDevices,
+			mask_len: mask.len() as c_int,
+			mask: mask.as_mut_ptr(),
+		};
+		xinput2::XISelectEvents(
+			display,
+			xlib::XDefaultRootWindow(display),
+			&mut event_mask,
+			1,
+		);
+		xlib::XSync(display, xlib::False);
+		Some(Self {
+			display,
+			opcode,
+			events: Box::leak(Box::new(InputEvents::default())),
+		})
+	}
+
+	/* Receive the events and update the shared input events. */
+	unsafe fn listen(&self) {
+		let mut event = MaybeUninit::<xlib::XEvent>::uninit();
+		loop {
+			xlib::XNextEvent(self.display, event.as_mut_ptr());
+			let cookie = &mut (*event.as_mut_ptr()).generic_event_cookie;
+			if cookie.type_ != xlib::GenericEvent
+				|| cookie.extension != self.opcode
+				|| xlib::XGetEventData(self.display, cookie) == xlib::False
+			{
+				continue;
+			}
+			let raw_event = &*(cookie.data as *const xinput2::XIRawEvent);
+			let code = raw_event.detail as u8;
+			let time = raw_event.time;
+			match cookie.evtype {
+				xinput2::XI_RawKeyPress | xinput2::XI_RawKeyRelease => {
+					if let Some(key) = get_keycode(code) {
+						self.events.push(
+							time,
+							if cookie.evtype == xinput2::XI_RawKeyPress {
+								InputEventKind::KeyPress(key)
+							} else {
+								InputEventKind::KeyRelease(key)
+							},
+							Some(code),
+						);
+					}
+				}
+				xinput2::XI_RawButtonPress => self.events.push(
+					time,
+					InputEventKind::ButtonPress(code as usize),
+					None,
+				),
+				xinput2::XI_RawButtonRelease => self.events.push(
+					time,
+					InputEventKind::ButtonRelease(code as usize),
+					None,
+				),
+				xinput2::XI_RawMotion => {
+					let (x, y) = self.get_pointer();
+					self.events.push(time, InputEventKind::Motion(x, y), None);
+				}
+				_ => {}
+			}
+			xlib::XFreeEventData(self.display, cookie);
+		}
+	}
+
+	/**
+	 * Get the position of the pointer on the root window.
+	 *
+	 * @return Tuple (i32, i32)
+	 */
+	unsafe fn get_pointer(&self) -> (i32, i32) {
+		let (mut root, mut child) = (0, 0);
+		let (mut x, mut y, mut window_x, mut window_y, mut mask) = (0, 0, 0, 0, 0);
+		xlib::XQueryPointer(
+			self.display,
+			xlib::XDefaultRootWindow(self.display),
+			&mut root,
+			&mut child,
+			&mut x,
+			&mut y,
+			&mut window_x,
+			&mut window_y,
+			&mut mask,
+		);
+		(x, y)
+	}
+}
+
+/**
+ * Get the Keycode from X keycode.
+ *
+ * @param  code
+ * @return Keycode (Option)
+ */
+fn get_keycode(code: u8) -> Option<Keycode> {
+	let letter =
+		|row: &str, index: u8| Keycode::from_str(&row[usize::from(index)..][..1]);
+	Some(match code.checked_sub(KEYCODE_OFFSET)? {
+		1 => Keycode::Escape,
+		code @ 2..=10 => Keycode::from_str(&format!("Key{}", code - 1)).ok()?,
+		11 => Keycode::Key0,
+		12 => Keycode::Minus,
+		13 => Keycode::Equal,
+		14 => Keycode::Backspace,
+		15 => Keycode::Tab,
+		code @ 16..=25 => letter("QWERTYUIOP", code - 16).ok()?,
+		26 => Keycode::LeftBracket,
+		27 => Keycode::RightBracket,
+		28 => Keycode::Enter,
+		29 => Keycode::LControl,
+		code @ 30..=38 => letter("ASDFGHJKL", code - 30).ok()?,
+		39 => Keycode::Semicolon,
+		40 => Keycode::Apostrophe,
+		41 => Keycode::Grave,
+		42 => Keycode::LShift,
+		43 => Keycode::BackSlash,
+		code @ 44..=50 => letter("ZXCVBNM", code - 44).ok()?,
+		51 => Keycode::Comma,
+		52 => Keycode::Dot,
+		53 => Keycode::Slash,
+		54 => Keycode::RShift,
+		56 => Keycode::LAlt,
+		57 => Keycode::Space,
+		58 => Keycode::CapsLock,
+		code @ 59..=68 => Keycode::from_str(&format!("F{}", code - 58)).ok()?,
+		87 => Keycode::F11,
+		88 => Keycode::F12,
+		97 => Keycode::RControl,
+		100 => Keycode::RAlt,
+		102 => Keycode::Home,
+		103 => Keycode::Up,
+		104 => Keycode::PageUp,
+		105 => Keycode::Left,
+		106 => Keycode::Right,
+		107 => Keycode::End,
+		108 => Keycode::Down,
+		109 => Keycode::PageDown,
+		110 => Keycode::Insert,
+		111 => Keycode::Delete,
+		125 | 126 => Keycode::Meta,
+		_ => return None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_keycode() {
+		for (code, key) in &[
+			(9, Keycode::Escape),
+			(10, Keycode::Key1),
+			(19, Keycode::Key0),
+			(24, Keycode::Q),
+			(38, Keycode::A),
+			(40, Keycode::D),
+			(58, Keycode::M),
+			(64, Keycode::LAlt),
+			(67, Keycode::F1),
+			(96, Keycode::F12),
+			(117, Keycode::PageDown),
+			(133, Keycode::Meta),
+		] {
+			assert_eq!(Some(key.clone()), get_keycode(*code));
+		}
+		assert_eq!(None, get_keycode(0));
+		assert_eq!(None, get_keycode(255));
+	}
+}
@@ -0,0 +1,217 @@
+use crate::image::geometry::Geometry;
+use crate::x11::display::Display;
+use std::convert::TryInto;
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::os::raw::{c_int, c_uint, c_ulong};
+use std::ptr;
+use x11::xlib;
+
+/* Size of the magnified area around the pointer */
+const LOUPE_AREA: u32 = 15;
+/* Zoom factor of the magnified area */
+const LOUPE_ZOOM: u32 = 8;
+/* Height of the text under the magnified area */
+const LOUPE_TEXT_HEIGHT: u32 = 16;
+/* Offset for placing the loupe next to the pointer */
+const LOUPE_OFFSET: i32 = 20;
+
+/* Magnifier window that follows the pointer */
+#[derive(Clone, Copy, Debug)]
+pub struct Loupe {
+	xid: c_ulong,
+	display: Display,
+	gc: xlib::GC,
+	root: Geometry,
+}
+
+impl Loupe {
+	/**
+	 * Create and show a new Loupe window.
+	 *
+	 * @param  display
+	 * @param  root
+	 * @return Loupe
+	 */
+	pub fn create(display: Display, root: Geometry) -> Self {
+		unsafe {
+			let (width, height) = Self::get_size();
+			let root_xid = xlib::XDefaultRootWindow(display.inner);
+			let mut attributes =
+				MaybeUninit::<xlib::XSetWindowAttributes>::zeroed().assume_init();
+			attributes.override_redirect = xlib::True;
+			attributes.background_pixel = xlib::XBlackPixel(
+				display.inner,
+				xlib::XDefaultScreen(display.inner),
+			);
+			attributes.border_pixel = display.settings.color as c_ulong;
+			let xid = xlib::XCreateWindow(
+				display.inner,
+				root_xid,
+				0,
+				0,
+				width,
+				height,
+				1,
+				xlib::CopyFromParent,
+				xlib::CopyFromParent as c_uint,
+				ptr::null_mut(),
+				xlib::CWOverrideRedirect | xlib::CWBackPixel | xlib::CWBorderPixel,
+				&mut attributes,
+			);
+			let gc = xlib::XCreateGC(display.inner, xid, 0, ptr::null_mut());
+			xlib::XSetForeground(
+				display.inner,
+				gc,
+				display.settings.color as c_ulong,
+			);
+			if let Some(xfont) = display.font {
+				xlib::XSetFont(display.inner, gc, (*xfont).fid);
+			}
+			xlib::XMapRaised(display.inner, xid);
+			Self {
+				xid,
+				display,
+				gc,
+				root,
+			}
+		}
+	}
+
+	/**
+	 * Get the size of the loupe window.
+	 *
+	 * @return Tuple (u32, u32)
+	 */
+	fn get_size() -> (u32, u32) {
+		(
+			LOUPE_AREA * LOUPE_ZOOM,
+			LOUPE_AREA * LOUPE_ZOOM + LOUPE_TEXT_HEIGHT,
+		)
+	}
+
+	/**
+	 * Get the position of the loupe window for the given pointer position.
+	 *
+	 * @param  pointer
+	 * @return Tuple (i32, i32)
+	 */
+	fn get_position(&self, pointer: (i32, i32)) -> (i32, i32) {
+		let (width, height) = Self::get_size();
+		let (width, height) = (width as i32, height as i32);
+		let mut x = pointer.0 + LOUPE_OFFSET;
+		let mut y = pointer.1 + LOUPE_OFFSET;
+		if x + width > self.root.x + self.root.width as i32 {
+			x = pointer.0 - LOUPE_OFFSET - width;
+		}
+		if y + height > self.root.y + self.root.height as i32 {
+			y = pointer.1 - LOUPE_OFFSET - height;
+		}
+		(x, y)
+	}
+
+	/**
+	 * Move the loupe next to the pointer and draw the magnified area.
+	 *
+	 * @param pointer
+	 * @param text
+	 */
+	pub fn update(&self, pointer: (i32, i32), text: &str) {
+		let (width, height) = Self::get_size();
+		let (x, y) = self.get_position(pointer);
+		let half = (LOUPE_AREA / 2) as i32;
+		let max_x = self.root.x + self.root.width.saturating_sub(LOUPE_AREA) as i32;
+		let max_y = self.root.y + self.root.height.saturating_sub(LOUPE_AREA) as i32;
+		let source_x = (pointer.0 - half).max(self.root.x).min(max_x);
+		let source_y = (pointer.1 - half).max(self.root.y).min(max_y);
+		unsafe {
+			xlib::XMoveWindow(self.display.inner, self.xid, x, y);
+			let source = xlib::XGetImage(
+				self.display.inner,
+				xlib::XDefaultRootWindow(self.display.inner),
+				source_x,
+				source_y,
+				LOUPE_AREA,
+				LOUPE_AREA,
+				xlib::XAllPlanes(),
+				xlib::ZPixmap,
+			);
+			if source.is_null() {
+				return;
+			}
+			let funcs = (*source).funcs;
+			if let (Some(sub_image), Some(get_pixel), Some(put_pixel)) =
+				(funcs.sub_image, funcs.get_pixel, funcs.put_pixel)
+			{
+				let zoomed = sub_image(source, 0, 0, width, width);
+				if !zoomed.is_null() {
+					for zoomed_y in 0..width as c_int {
+						for zoomed_x in 0..width as c_int {
+							put_pixel(
+								zoomed,
+								zoomed_x,
+								zoomed_y,
+								get_pixel(
+									source,
+									zoomed_x / LOUPE_ZOOM as c_int,
+									zoomed_y / LOUPE_ZOOM as c_int,
+								),
+							);
+						}
+					}
+					xlib::XPutImage(
+						self.display.inner,
+						self.xid,
+						self.gc,
+						zoomed,
+						0,
+						0,
+						0,
+						0,
+						width,
+						width,
+					);
+					xlib::XDestroyImage(zoomed);
+				}
+			}
+			xlib::XDestroyImage(source);
+			xlib::XDrawRectangle(
+				self.display.inner,
+				self.xid,
+				self.gc,
+				(pointer.0 - source_x) * LOUPE_ZOOM as i32,
+				(pointer.1 - source_y) * LOUPE_ZOOM as i32,
+				LOUPE_ZOOM,
+				LOUPE_ZOOM,
+			);
+			xlib::XClearArea(
+				self.display.inner,
+				self.xid,
+				0,
+				width as i32,
+				width,
+				height - width,
+				xlib::False,
+			);
+			xlib::XDrawString(
+				self.display.inner,
+				self.xid,
+				self.gc,
+				4,
+				height as i32 - 4,
+				CString::new(text).unwrap_or_default().as_ptr(),
+				text.len().try_into().unwrap_or_default(),
+			);
+			xlib::XFlush(self.display.inner);
+		}
+	}
+
+	/* Destroy the loupe window. */
+	pub fn destroy(&self) {
+		unsafe {
+			xlib::XFreeGC(self.display.inner, self.gc);
+			xlib::XDestroyWindow(self.display.inner, self.xid);
+			xlib::XFlush(self.display.inner);
+		}
+	}
+}
@@ -1,4 +1,10 @@
+pub mod daemon;
 pub mod display;
+pub mod format;
+pub mod input;
+pub mod loupe;
+pub mod output;
+pub mod server;
 pub mod window;
 
 use crate::record::settings::RecordWindow;
@@ -41,6 +47,14 @@ impl<'a> Access<'a, Window> for WindowSystem<'a> {
 	 */
 	fn get_window(&mut self) -> Option<Window> {
 		debug!("Record window: {:?}", self.settings.record.window);
+		if self.settings.record.flag.drag {
+			return self.display.select_area(
+				&self
+					.settings
+					.input_state
+					.expect("Failed to get the input state"),
+			);
+		}
 		match self.settings.record.window {
 			RecordWindow::Focus(None, parent) => {
 				self.display.get_focused_window(parent)
@@ -0,0 +1,191 @@
+use crate::image::geometry::Geometry;
+use image::Bgra;
+use std::convert::TryFrom;
+use std::fmt;
+
+/* RandR output (monitor) with its name and geometry */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Output {
+	pub name: String,
+	pub geometry: Geometry,
+	pub primary: bool,
+}
+
+/* Display implementation for user-facing output */
+impl fmt::Display for Output {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} {}+{}+{}{}",
+			self.name,
+			self.geometry,
+			self.geometry.x,
+			self.geometry.y,
+			if self.primary { " (primary)" } else { "" }
+		)
+	}
+}
+
+impl Output {
+	/**
+	 * Create a new Output object.
+	 *
+	 * @param  name
+	 * @param  geometry
+	 * @param  primary
+	 * @return Output
+	 */
+	pub fn new(name: &str, geometry: Geometry, primary: bool) -> Self {
+		Self {
+			name: name.to_string(),
+			geometry,
+			primary,
+		}
+	}
+
+	/**
+	 * Select outputs from the given list.
+	 *
+	 * Monitors are separated by commas and specified either
+	 * by their number (starting from 1), RandR output name,
+	 * "primary" or "all".
+	 *
+	 * @param  outputs
+	 * @param  monitors
+	 * @return Vector of Output (Option)
+	 */
+	pub fn select(outputs: &[Self], monitors: &str) -> Option<Vec<Self>> {
+		let mut selected = Vec::new();
+		for monitor in monitors.split(',').map(str::trim) {
+			match monitor {
+				"all" => selected.extend(outputs.iter().cloned()),
+				"primary" => selected.push(
+					outputs
+						.iter()
+						.find(|output| output.primary)
+						.or_else(|| outputs.first())?
+						.clone(),
+				),
+				_ => selected.push(
+					match monitor.parse::<usize>() {
+						Ok(num) => outputs.get(num.saturating_sub(1)),
+						Err(_) => {
+							outputs.iter().find(|output| output.name == monitor)
+						}
+					}?
+					.clone(),
+				),
+			}
+		}
+		selected.dedup();
+		if selected.is_empty() {
+			None
+		} else {
+			Some(selected)
+		}
+	}
+
+	/**
+	 * Get the bounding box of the given geometries.
+	 *
+	 * @param  geometries
+	 * @return Geometry
+	 */
+	pub fn get_bounds(geometries: &[Geometry]) -> Geometry {
+		let x = geometries.iter().map(|g| g.x).min().unwrap_or_default();
+		let y = geometries.iter().map(|g| g.y).min().unwrap_or_default();
+		Geometry::new(
+			x,
+			y,
+			geometries
+				.iter()
+				.map(|g| (g.x - x) as u32 + g.width)
+				.max()
+				.unwrap_or_default(),
+			geometries
+				.iter()
+				.map(|g| (g.y - y) as u32 + g.height)
+				.max()
+				.unwrap_or_default(),
+		)
+	}
+}
+
+/**
+ * Fill the pixels that are not on any of the monitors.
+ *
+ * @param data
+ * @param area
+ * @param monitors
+ * @param color
+ */
+pub fn fill_gaps(
+	data: &mut [Bgra<u8>],
+	area: Geometry,
+	monitors: &[Geometry],
+	color: u64,
+) {
+	let color =
+		Bgra::from([color as u8, (color >> 8) as u8, (color >> 16) as u8, 255]);
+	let width = usize::try_from(area.width).unwrap_or_default().max(1);
+	for (i, pixel) in data.iter_mut().enumerate() {
+		let x = area.x + (i % width) as i32;
+		let y = area.y + (i / width) as i32;
+		if !monitors.iter().any(|monitor| {
+			x >= monitor.x
+				&& y >= monitor.y
+				&& x < monitor.x + monitor.width as i32
+				&& y < monitor.y + monitor.height as i32
+		}) {
+			*pixel = color;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_output() {
+		let outputs = [
+			Output::new("eDP-1", Geometry::new(0, 312, 1366, 768), false),
+			Output::new("DP-1", Geometry::new(1366, 0, 1920, 1080), true),
+		];
+		assert_eq!("DP-1 1920x1080+1366+0 (primary)", outputs[1].to_string());
+		for (monitors, expected) in &[
+			("1", vec![0]),
+			("0", vec![0]),
+			("DP-1", vec![1]),
+			("primary", vec![1]),
+			("eDP-1, primary", vec![0, 1]),
+			("all", vec![0, 1]),
+		] {
+			assert_eq!(
+				Some(expected.iter().map(|i| outputs[*i].clone()).collect()),
+				Output::select(&outputs, monitors)
+			);
+		}
+		assert_eq!(None, Output::select(&outputs, "3"));
+		assert_eq!(None, Output::select(&outputs, "HDMI-1"));
+		let monitors = [outputs[0].geometry, outputs[1].geometry];
+		let bounds = Output::get_bounds(&monitors);
+		assert_eq!(Geometry::new(0, 0, 3286, 1080), bounds);
+		let mut data = vec![Bgra::from([1, 1, 1, 1]); 4];
+		fill_gaps(
+			&mut data,
+			Geometry::new(1365, 311, 2, 2),
+			&monitors,
+			0x00FF_0000,
+		);
+		assert_eq!(
+			vec![
+				Bgra::from([0, 0, 255, 255]),
+				Bgra::from([1, 1, 1, 1]),
+				Bgra::from([1, 1, 1, 1]),
+				Bgra::from([1, 1, 1, 1]),
+			],
+			data
+		);
+	}
+}
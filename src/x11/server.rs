@@ -0,0 +1,169 @@
+use crate::record::settings::RecordServer;
+use std::env;
+use std::ffi::CString;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::process::{Child, Command as OsCommand, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use x11::xlib;
+
+/* First display number to try for the private server */
+const DISPLAY_NUMBER_START: u32 = 99;
+/* Maximum number of displays to try */
+const DISPLAY_NUMBER_COUNT: u32 = 100;
+/* Timeout for waiting the server to start (in milliseconds) */
+const SERVER_TIMEOUT: u64 = 5000;
+/* Interval for checking if the server is ready (in milliseconds) */
+const SERVER_POLL_INTERVAL: u64 = 50;
+
+/* Private X server for headless recording */
+#[derive(Debug)]
+pub struct XServer {
+	pub display: String,
+	process: Child,
+}
+
+impl XServer {
+	/**
+	 * Start a new X server and set the DISPLAY variable.
+	 *
+	 * @param  server
+	 * @return XServer (Result)
+	 */
+	pub fn start(server: RecordServer) -> Result<Self, Error> {
+		let display = format!(":{}", Self::get_free_display()?);
+		let (program, args) = Self::get_command(server, &display);
+		info!("Starting {} on display {}...", program, display);
+		let mut server = Self {
+			process: OsCommand::new(program)
+				.args(&args)
+				.stdout(Stdio::null())
+				.stderr(Stdio::null())
+				.spawn()?,
+			display,
+		};
+		server.wait()?;
+		env::set_var("DISPLAY", &server.display);
+		Ok(server)
+	}
+
+	/**
+	 * Get the server program and its arguments.
+	 *
+	 * @param  server
+	 * @param  display
+	 * @return Tuple (str, Vector of String)
+	 */
+	fn get_command(server: RecordServer, display: &str) -> (&str, Vec<String>) {
+		match server {
+			RecordServer::Xvfb(geometry) => (
+				"Xvfb",
+				vec![
+					display.to_string(),
+					String::from("-screen"),
+					String::from("0"),
+					format!("{}x24", geometry),
+					String::from("-nolisten"),
+					String::from("tcp"),
+				],
+			),
+			RecordServer::Xephyr(geometry) => (
+				"Xephyr",
+				vec![
+					display.to_string(),
+					String::from("-screen"),
+					geometry.to_string(),
+					String::from("-nolisten"),
+					String::from("tcp"),
+				],
+			),
+		}
+	}
+
+	/**
+	 * Get a display number that is not in use.
+	 *
+	 * @return u32 (Result)
+	 */
+	fn get_free_display() -> Result<u32, Error> {
+		(DISPLAY_NUMBER_START..DISPLAY_NUMBER_START + DISPLAY_NUMBER_COUNT)
+			.find(|number| {
+				!Path::new(&format!("/tmp/.X{}-lock", number)).exists()
+					&& !Path::new(&format!("/tmp/.X11-unix/X{}", number)).exists()
+			})
+			.ok_or_else(|| {
+				Error::new(ErrorKind::AddrInUse, "No free display number found")
+			})
+	}
+
+	/**
+	 * Wait for the server to accept connections.
+	 *
+	 * @return Result
+	 */
+	fn wait(&mut self) -> Result<(), Error> {
+		let display =
+			CString::new(self.display.as_str()).expect("Failed to create CString");
+		let start_time = Instant::now();
+		while start_time.elapsed() < Duration::from_millis(SERVER_TIMEOUT) {
+			if let Some(status) = self.process.try_wait()? {
+				return Err(Error::new(
+					ErrorKind::ConnectionRefused,
+					format!("X server exited unexpectedly ({})", status),
+				));
+			}
+			unsafe {
+				let inner = xlib::XOpenDisplay(display.as_ptr());
+				if !inner.is_null() {
+					xlib::XCloseDisplay(inner);
+					return Ok(());
+				}
+			}
+			thread::sleep(Duration::from_millis(SERVER_POLL_INTERVAL));
+		}
+		Err(Error::new(
+			ErrorKind::TimedOut,
+			"Timed out while waiting for the X server",
+		))
+	}
+}
+
+/* Stop the server when it goes out of scope */
+impl Drop for XServer {
+	fn drop(&mut self) {
+		if self.process.kill().is_ok() {
+			self.process.wait().unwrap_or_else(|_| {
+				panic!("Failed to wait for the X server ({})", self.display)
+			});
+			debug!("Stopped the X server on display {}", self.display);
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "test-ws")]
+mod tests {
+	use super::*;
+	use crate::image::geometry::Geometry;
+	use crate::x11::display::Display;
+	use pretty_assertions::assert_eq;
+	#[test]
+	fn test_x11_server() {
+		let display = env::var("DISPLAY").unwrap_or_default();
+		let server =
+			XServer::start(RecordServer::Xvfb(Geometry::new(0, 0, 320, 240)))
+				.unwrap();
+		assert_eq!(Ok(server.display.clone()), env::var("DISPLAY"));
+		let window = Display::open(None).unwrap().get_root_window();
+		assert_eq!(Geometry::new(0, 0, 320, 240), window.geometry);
+		drop(server);
+		env::set_var("DISPLAY", display);
+		assert!(XServer::get_command(
+			RecordServer::Xephyr(Geometry::new(0, 0, 1, 1)),
+			":1"
+		)
+		.1
+		.contains(&String::from("1x1")));
+	}
+}
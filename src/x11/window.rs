@@ -1,8 +1,11 @@
 use crate::image::geometry::Geometry;
 use crate::image::Image;
+use crate::measure::Measurement;
 use crate::record::fps::FpsClock;
 use crate::window::Capture;
 use crate::x11::display::Display;
+use crate::x11::format::PixelFormat;
+use crate::x11::output::{self, Output};
 use image::Bgra;
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CString;
@@ -167,15 +170,45 @@ impl Window {
 	}
 
 	/**
-	 * Get CRTC (VDC) information of the window.
+	 * Get the child window that contains the pointer.
 	 *
-	 * @param Vector of Geometry
+	 * @return Window (Option)
+	 */
+	pub fn get_child_at_pointer(&self) -> Option<Self> {
+		let (mut root, mut child) = (0, 0);
+		let (mut root_x, mut root_y, mut x, mut y, mut mask) = (0, 0, 0, 0, 0);
+		unsafe {
+			if xlib::XQueryPointer(
+				self.display.inner,
+				self.xid,
+				&mut root,
+				&mut child,
+				&mut root_x,
+				&mut root_y,
+				&mut x,
+				&mut y,
+				&mut mask,
+			) == xlib::True
+				&& child != 0
+			{
+				Some(Window::new(child, self.display))
+			} else {
+				None
+			}
+		}
+	}
+
+	/**
+	 * Get the RandR outputs (monitors) of the window.
+	 *
+	 * @return Vector of Output
 	 */
-	pub fn get_crtc_info(&self) -> Vec<Geometry> {
-		let mut crtc_info = Vec::new();
+	pub fn get_outputs(&self) -> Vec<Output> {
+		let mut outputs = Vec::new();
 		unsafe {
 			let resources =
 				xrandr::XRRGetScreenResources(self.display.inner, self.xid);
+			let primary = xrandr::XRRGetOutputPrimary(self.display.inner, self.xid);
 			for crtc in slice::from_raw_parts(
 				(*resources).crtcs,
 				(*resources).ncrtc.try_into().unwrap_or_default(),
@@ -184,18 +217,35 @@ impl Window {
 			.map(|v| xrandr::XRRGetCrtcInfo(self.display.inner, resources, *v))
 			{
 				if (*crtc).noutput > 0 {
-					crtc_info.push(Geometry::new(
-						(*crtc).x as i32,
-						(*crtc).y as i32,
-						(*crtc).width as u32,
-						(*crtc).height as u32,
+					let crtc_outputs = slice::from_raw_parts(
+						(*crtc).outputs,
+						(*crtc).noutput.try_into().unwrap_or_default(),
+					);
+					let info = xrandr::XRRGetOutputInfo(
+						self.display.inner,
+						resources,
+						crtc_outputs[0],
+					);
+					outputs.push(Output::new(
+						&String::from_utf8_lossy(slice::from_raw_parts(
+							(*info).name as *const u8,
+							(*info).nameLen.try_into().unwrap_or_default(),
+						)),
+						Geometry::new(
+							(*crtc).x as i32,
+							(*crtc).y as i32,
+							(*crtc).width as u32,
+							(*crtc).height as u32,
+						),
+						crtc_outputs.contains(&primary),
 					));
+					xrandr::XRRFreeOutputInfo(info);
 				}
 				xrandr::XRRFreeCrtcInfo(crtc);
 			}
 			xrandr::XRRFreeScreenResources(resources);
 		}
-		crtc_info
+		outputs
 	}
 
 	/**
@@ -256,6 +306,39 @@ impl Window {
 		}
 	}
 
+	/**
+	 * Get the graphics context for drawing over the subwindows with XOR.
+	 *
+	 * @return GC
+	 */
+	pub unsafe fn get_xor_gc(&self) -> xlib::GC {
+		let gc = self.get_gc();
+		xlib::XSetFunction(self.display.inner, gc, xlib::GXxor);
+		xlib::XSetSubwindowMode(self.display.inner, gc, xlib::IncludeInferiors);
+		gc
+	}
+
+	/**
+	 * Draw (or erase if it is already drawn) a selection rectangle.
+	 *
+	 * @param gc
+	 * @param area
+	 */
+	pub fn draw_selection(&self, gc: xlib::GC, area: Geometry) {
+		unsafe {
+			xlib::XDrawRectangle(
+				self.display.inner,
+				self.xid,
+				gc,
+				area.x,
+				area.y,
+				area.width,
+				area.height,
+			);
+			xlib::XFlush(self.display.inner);
+		}
+	}
+
 	/**
 	 * Draw a text on the window.
 	 *
@@ -264,11 +347,23 @@ impl Window {
 	 * @param y
 	 */
 	fn draw_text(&self, text: &str, x: i32, y: i32) {
+		self.draw_text_with_gc(unsafe { self.get_gc() }, text, x, y);
+	}
+
+	/**
+	 * Draw a text on the window using the given graphics context.
+	 *
+	 * @param gc
+	 * @param text
+	 * @param x
+	 * @param y
+	 */
+	fn draw_text_with_gc(&self, gc: xlib::GC, text: &str, x: i32, y: i32) {
 		unsafe {
 			xlib::XDrawString(
 				self.display.inner,
 				self.xid,
-				self.get_gc(),
+				gc,
 				x,
 				y,
 				CString::new(text).unwrap_or_default().as_ptr(),
@@ -277,6 +372,113 @@ impl Window {
 		}
 	}
 
+	/**
+	 * Draw (or erase if it is already drawn) a measurement with its label.
+	 *
+	 * @param gc
+	 * @param measurement
+	 */
+	pub fn draw_measurement(&self, gc: xlib::GC, measurement: Measurement) {
+		if measurement.area {
+			self.draw_selection(gc, measurement.get_geometry());
+		} else {
+			unsafe {
+				xlib::XDrawLine(
+					self.display.inner,
+					self.xid,
+					gc,
+					measurement.start.0,
+					measurement.start.1,
+					measurement.end.0,
+					measurement.end.1,
+				);
+			}
+		}
+		self.draw_text_with_gc(
+			gc,
+			&measurement.to_string(),
+			measurement.end.0 + TEXT_CORNER_OFFSET / 2,
+			measurement.end.1 - TEXT_CORNER_OFFSET / 2,
+		);
+		unsafe { xlib::XFlush(self.display.inner) };
+	}
+
+	/**
+	 * Get the pixels of a text that is rendered with the display font.
+	 *
+	 * @param  text
+	 * @return Tuple (Geometry, Vector of bool) (Option)
+	 */
+	pub fn get_text_mask(&self, text: &str) -> Option<(Geometry, Vec<bool>)> {
+		let font = self.display.font?;
+		let c_text = CString::new(text).ok()?;
+		let length = text.len().try_into().unwrap_or_default();
+		unsafe {
+			let width =
+				u32::try_from(xlib::XTextWidth(font, c_text.as_ptr(), length))
+					.unwrap_or_default();
+			let height =
+				u32::try_from((*font).ascent + (*font).descent).unwrap_or_default();
+			if width == 0 || height == 0 {
+				return None;
+			}
+			let pixmap =
+				xlib::XCreatePixmap(self.display.inner, self.xid, width, height, 1);
+			let gc = xlib::XCreateGC(self.display.inner, pixmap, 0, ptr::null_mut());
+			xlib::XSetForeground(self.display.inner, gc, 0);
+			xlib::XFillRectangle(
+				self.display.inner,
+				pixmap,
+				gc,
+				0,
+				0,
+				width,
+				height,
+			);
+			xlib::XSetForeground(self.display.inner, gc, 1);
+			xlib::XSetFont(self.display.inner, gc, (*font).fid);
+			xlib::XDrawString(
+				self.display.inner,
+				pixmap,
+				gc,
+				0,
+				(*font).ascent,
+				c_text.as_ptr(),
+				length,
+			);
+			let text_image = xlib::XGetImage(
+				self.display.inner,
+				pixmap,
+				0,
+				0,
+				width,
+				height,
+				1,
+				xlib::XYPixmap,
+			);
+			let mask = if text_image.is_null() {
+				None
+			} else {
+				let mask = (*text_image).funcs.get_pixel.map(|get_pixel| {
+					(0..(width * height))
+						.map(|i| {
+							get_pixel(
+								text_image,
+								(i % width).try_into().unwrap_or_default(),
+								(i / width).try_into().unwrap_or_default(),
+							) != 0
+						})
+						.collect()
+				});
+				xlib::XDestroyImage(text_image);
+				mask
+			};
+			xlib::XFreeGC(self.display.inner, gc);
+			xlib::XFreePixmap(self.display.inner, pixmap);
+			Some((Geometry::new(0, 0, width, height), mask?))
+		}
+	}
+
 	/**
 	 * Show a text on the window for a given duration.
 	 *
@@ -353,18 +555,55 @@ impl Window {
 	 * @param key
 	 */
 	pub fn grab_key(&self, key: c_ulong) {
+		self.grab_key_with_modifiers(key, xlib::AnyModifier);
+	}
+
+	/**
+	 * Grab a key with the given modifiers in the window.
+	 *
+	 * @param key
+	 * @param modifiers
+	 */
+	pub fn grab_key_with_modifiers(&self, key: c_ulong, modifiers: c_uint) {
 		unsafe {
 			xlib::XGrabKey(
 				self.display.inner,
 				xlib::XKeysymToKeycode(self.display.inner, key).into(),
-				xlib::AnyModifier,
+				modifiers,
 				self.xid,
 				xlib::False,
 				xlib::GrabModeAsync,
 				xlib::GrabModeAsync,
 			);
 		}
-		trace!("Grabbed the key {} of {:?}", key, self.xid);
+		trace!("Grabbed the key {} ({}) of {:?}", key, modifiers, self.xid);
+	}
+
+	/**
+	 * Get the color of the pixel at the given position.
+	 *
+	 * @param  position
+	 * @return Bgra (Option)
+	 */
+	pub fn get_pixel(&self, position: (i32, i32)) -> Option<Bgra<u8>> {
+		unsafe {
+			let pixel_image = xlib::XGetImage(
+				self.display.inner,
+				self.xid,
+				position.0,
+				position.1,
+				1,
+				1,
+				xlib::XAllPlanes(),
+				xlib::ZPixmap,
+			);
+			if pixel_image.is_null() {
+				return None;
+			}
+			let pixels = PixelFormat::get_pixels(&*pixel_image);
+			xlib::XDestroyImage(pixel_image);
+			pixels?.first().copied()
+		}
 	}
 
 	/* Ungrab the keys in the window.*/
@@ -401,13 +640,17 @@ impl Capture for Window {
 				xlib::ZPixmap,
 			);
 			if !window_image.is_null() {
-				let image = &mut *window_image;
-				let data = slice::from_raw_parts::<Bgra<u8>>(
-					image.data as *const Bgra<u8>,
-					image.width as usize * image.height as usize,
-				)
-				.to_vec();
+				let data = PixelFormat::get_pixels(&*window_image);
 				xlib::XDestroyImage(window_image);
+				let mut data = data?;
+				if let Some(monitors) = self.display.monitors {
+					output::fill_gaps(
+						&mut data,
+						self.area,
+						monitors,
+						self.display.settings.background,
+					);
+				}
 				Some(Image::new(
 					data,
 					self.display.settings.flag.alpha,
@@ -489,7 +732,10 @@ mod tests {
 		window.draw_borders();
 		window.show_countdown();
 		window.clear_area();
-		assert_eq!(Geometry::new(0, 0, 1366, 768), window.get_crtc_info()[0]);
+		assert_eq!(
+			Geometry::new(0, 0, 1366, 768),
+			window.get_outputs()[0].geometry
+		);
 		assert_eq!(0, unsafe { window.get_parent() }.unwrap().xid);
 		assert_eq!(
 			"\n Window title  -> \"root-window\"\n Window size   -> [1366x768]",